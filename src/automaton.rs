@@ -0,0 +1,353 @@
+// Cellular automata on a `Grid`: every step, each cell's new value comes
+// from its old value and its neighbours', as decided by a `Rule`. Rules can
+// also cascade - cells which "fire" after a step push on their neighbours,
+// which may fire in turn, until things settle down.
+//
+// The grid is either all there is (`Boundary::Bounded`), or a window on an
+// infinite plane where every cell outside it has the same value
+// (`Boundary::Infinite`). In that case the window grows by one cell on each
+// side per step, since that's as far as anything can spread.
+
+use std::{collections::VecDeque, fmt::Debug};
+
+use crate::grid::{Grid, Pos};
+
+/// Which cells count as neighbours, as (row, col) offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// Up, down, left and right
+    Orthogonal,
+    /// The orthogonal neighbours, then the diagonals
+    AllAround,
+    /// The 3x3 square centred on the cell (including itself), row by row
+    Square,
+}
+
+impl Neighbourhood {
+    pub fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Neighbourhood::Orthogonal => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+            Neighbourhood::AllAround => &[
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+                (-1, -1),
+                (-1, 1),
+                (1, -1),
+                (1, 1),
+            ],
+            Neighbourhood::Square => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 0),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Boundary<T> {
+    /// Nothing exists outside the grid, so edge cells have fewer neighbours.
+    Bounded,
+    /// Outside the grid, every cell holds this value.
+    Infinite(T),
+}
+
+pub trait Rule {
+    type Cell: Clone + PartialEq + Debug;
+
+    fn neighbourhood(&self) -> Neighbourhood;
+
+    /// A cell's value after a step, from its current value and its
+    /// neighbours' (in `neighbourhood()` order, skipping any off the edge
+    /// of a bounded grid).
+    fn next(&self, cell: &Self::Cell, neighbours: &[Self::Cell]) -> Self::Cell;
+
+    /// After `next()`, does this cell fire? Each cell fires at most once
+    /// per step.
+    fn fires(&self, _cell: &Self::Cell) -> bool {
+        false
+    }
+
+    /// The effect on a cell of one of its neighbours firing.
+    fn spread(&self, _cell: &mut Self::Cell) {}
+
+    /// Last change to each cell once the cascade is over.
+    fn settle(&self, _cell: &mut Self::Cell, _fired: bool) {}
+}
+
+#[derive(Debug, Clone)]
+pub struct Automaton<R: Rule> {
+    rule: R,
+    cells: Grid<R::Cell>,
+    boundary: Boundary<R::Cell>,
+
+    generation: usize,
+    // cells which fired during the last step, and in all steps so far
+    fired: usize,
+    total_fired: usize,
+}
+
+impl<R: Rule> Automaton<R> {
+    pub fn new(rule: R, cells: Grid<R::Cell>, boundary: Boundary<R::Cell>) -> Self {
+        Self {
+            rule,
+            cells,
+            boundary,
+            generation: 0,
+            fired: 0,
+            total_fired: 0,
+        }
+    }
+
+    pub fn cells(&self) -> &Grid<R::Cell> {
+        &self.cells
+    }
+
+    pub fn boundary(&self) -> &Boundary<R::Cell> {
+        &self.boundary
+    }
+
+    /// Number of steps taken so far
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Number of cells which fired in the last step
+    pub fn fired(&self) -> usize {
+        self.fired
+    }
+
+    pub fn total_fired(&self) -> usize {
+        self.total_fired
+    }
+
+    fn cell_at(&self, row: isize, col: isize) -> Option<&R::Cell> {
+        let cell = match (usize::try_from(row), usize::try_from(col)) {
+            (Ok(row), Ok(col)) => self.cells.get((row, col)),
+            _ => None,
+        };
+        match &self.boundary {
+            Boundary::Bounded => cell,
+            Boundary::Infinite(background) => cell.or(Some(background)),
+        }
+    }
+
+    /// Advance one step.
+    pub fn step(&mut self) {
+        let offsets = self.rule.neighbourhood().offsets();
+        // how far the old grid is from the top left of the new one
+        let border = match self.boundary {
+            Boundary::Bounded => 0,
+            Boundary::Infinite(_) => 1,
+        };
+
+        let mut neighbours = Vec::with_capacity(offsets.len());
+        let mut cells = Grid::from_fn(
+            self.cells.width() + 2 * border,
+            self.cells.height() + 2 * border,
+            |(row, col)| {
+                let (row, col) = (
+                    row as isize - border as isize,
+                    col as isize - border as isize,
+                );
+                neighbours.clear();
+                neighbours.extend(
+                    offsets
+                        .iter()
+                        .filter_map(|&(dr, dc)| self.cell_at(row + dr, col + dc))
+                        .cloned(),
+                );
+                let cell = self.cell_at(row, col).expect("cell is in the grid");
+                self.rule.next(cell, &neighbours)
+            },
+        );
+
+        let fired = self.cascade(&mut cells, offsets);
+        for (cell, &fired) in cells.cells_mut().zip(fired.cells()) {
+            self.rule.settle(cell, fired);
+        }
+        self.fired = fired.cells().filter(|&&f| f).count();
+        self.total_fired += self.fired;
+
+        if let Boundary::Infinite(background) = &self.boundary {
+            // Far enough away, everything around a cell is background too.
+            let mut next = self
+                .rule
+                .next(background, &vec![background.clone(); offsets.len()]);
+            self.rule.settle(&mut next, false);
+            self.boundary = Boundary::Infinite(next);
+        }
+
+        self.cells = cells;
+        self.generation += 1;
+    }
+
+    // Fire everything that's ready to, and anything that sets off, marking
+    // which cells fired.
+    fn cascade(&self, cells: &mut Grid<R::Cell>, offsets: &[(isize, isize)]) -> Grid<bool> {
+        let mut fired = Grid::new(cells.width(), cells.height(), false);
+        let mut ready: VecDeque<Pos> = cells
+            .iter()
+            .filter(|(_, cell)| self.rule.fires(cell))
+            .map(|(pos, _)| pos)
+            .collect();
+        while let Some(pos) = ready.pop_front() {
+            if fired[pos] {
+                continue;
+            }
+            fired[pos] = true;
+            for &(dr, dc) in offsets {
+                let n = match (pos.0.checked_add_signed(dr), pos.1.checked_add_signed(dc)) {
+                    (Some(row), Some(col)) if (dr, dc) != (0, 0) => (row, col),
+                    _ => continue,
+                };
+                if let Some(cell) = cells.get_mut(n) {
+                    self.rule.spread(cell);
+                    if !fired[n] && self.rule.fires(cell) {
+                        ready.push_back(n);
+                    }
+                }
+            }
+        }
+        fired
+    }
+
+    /// Take `steps` more steps.
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    /// Step until `done` is true afterwards, returning the generation
+    /// reached. Never returns if that doesn't happen!
+    pub fn run_until(&mut self, mut done: impl FnMut(&Self) -> bool) -> usize {
+        loop {
+            self.step();
+            if done(self) {
+                break self.generation;
+            }
+        }
+    }
+
+    /// Step until a step changes nothing, returning the generation of that
+    /// unchanging step. Never returns if that doesn't happen!
+    pub fn run_to_fixpoint(&mut self) -> usize {
+        loop {
+            let (cells, boundary) = (self.cells.clone(), self.boundary.clone());
+            self.step();
+            if self.cells == cells && self.boundary == boundary {
+                break self.generation;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::Input;
+
+    // Conway's Game of Life
+    #[derive(Debug, Clone)]
+    struct Life;
+
+    impl Rule for Life {
+        type Cell = bool;
+
+        fn neighbourhood(&self) -> Neighbourhood {
+            Neighbourhood::AllAround
+        }
+
+        fn next(&self, &alive: &bool, neighbours: &[bool]) -> bool {
+            let count = neighbours.iter().filter(|&&n| n).count();
+            count == 3 || (alive && count == 2)
+        }
+    }
+
+    fn pixels(s: &str) -> Grid<bool> {
+        Grid::pixels(Input::from_text(s).lines()).unwrap()
+    }
+
+    fn render(grid: &Grid<bool>) -> String {
+        grid.render(|&p| if p { '#' } else { '.' })
+    }
+
+    #[test]
+    fn test_bounded() {
+        let mut life = Automaton::new(Life, pixels(".#.\n.#.\n.#."), Boundary::Bounded);
+        life.step();
+        assert_eq!(render(life.cells()), "...\n###\n...");
+        life.run(3);
+        assert_eq!(render(life.cells()), ".#.\n.#.\n.#.");
+        assert_eq!(life.generation(), 4);
+
+        // a glider hits the bottom right corner and ends up a block
+        let glider = ".#....\n..#...\n###...\n......\n......\n......";
+        let mut life = Automaton::new(Life, pixels(glider), Boundary::Bounded);
+        assert_eq!(life.run_to_fixpoint(), 16);
+        assert_eq!(
+            render(life.cells()),
+            "......\n......\n......\n......\n....##\n....##"
+        );
+    }
+
+    #[test]
+    fn test_infinite() {
+        let mut life = Automaton::new(Life, pixels("###"), Boundary::Infinite(false));
+        life.step();
+        assert_eq!(render(life.cells()), "..#..\n..#..\n..#..");
+        assert_eq!(life.boundary(), &Boundary::Infinite(false));
+    }
+
+    // Counters which fire above 3, bumping their neighbours, and reset to
+    // zero after firing
+    #[derive(Debug, Clone)]
+    struct Chain;
+
+    impl Rule for Chain {
+        type Cell = u8;
+
+        fn neighbourhood(&self) -> Neighbourhood {
+            Neighbourhood::Orthogonal
+        }
+
+        fn next(&self, cell: &u8, _: &[u8]) -> u8 {
+            cell + 1
+        }
+
+        fn fires(&self, cell: &u8) -> bool {
+            *cell > 3
+        }
+
+        fn spread(&self, cell: &mut u8) {
+            *cell += 1;
+        }
+
+        fn settle(&self, cell: &mut u8, fired: bool) {
+            if fired {
+                *cell = 0;
+            }
+        }
+    }
+
+    #[test]
+    fn test_cascade() {
+        let cells = Grid::digits(Input::from_text("3210\n0000").lines()).unwrap();
+        let mut chain = Automaton::new(Chain, cells, Boundary::Bounded);
+        chain.step();
+        // the 3 fires, setting off the 2, but the 1 only gets to 3
+        assert_eq!(chain.fired(), 2);
+        assert_eq!(chain.cells().render(|&d| (b'0' + d) as char), "0031\n2211");
+        assert_eq!(chain.run_until(|c| c.fired() == 8), 3);
+        assert_eq!(chain.total_fired(), 2 + 1 + 8);
+    }
+}
@@ -0,0 +1,249 @@
+// Timing for each day and part, run repeatedly so the numbers mean
+// something. Parsing and solving are timed separately, and results can be
+// saved as a baseline for later runs to be compared against.
+
+use std::{
+    collections::HashMap,
+    fs, hint,
+    time::{Duration, Instant},
+};
+
+use aoc2021::{
+    input::{Input, InputError},
+    Day,
+};
+
+use crate::cli::BenchOptions;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Summary {
+    fn from_samples(samples: &mut [Duration]) -> Self {
+        samples.sort_unstable();
+        Self {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BenchResult {
+    pub day: u32,
+    pub part: usize,
+    pub parse: Summary,
+    pub solve: Summary,
+}
+
+impl BenchResult {
+    /// The figure used for baseline comparisons
+    fn total(&self) -> Duration {
+        self.parse.median + self.solve.median
+    }
+}
+
+fn bench_day(
+    day: &Day,
+    parts: &[usize],
+    path: Option<&str>,
+    runs: usize,
+) -> Result<Vec<BenchResult>, InputError> {
+    // Reading the file isn't counted; only parsing the text.
+    let input = day.input(path)?;
+    let parts: Vec<usize> = parts.iter().copied().filter(|&p| p <= day.parts).collect();
+
+    let mut parse_times = vec![];
+    let mut solve_times = vec![vec![]; parts.len()];
+    for _ in 0..runs {
+        let start = Instant::now();
+        let solution = (day.parse)(&input)?;
+        parse_times.push(start.elapsed());
+
+        for (idx, &part) in parts.iter().enumerate() {
+            let start = Instant::now();
+            hint::black_box(solution.solve(part));
+            solve_times[idx].push(start.elapsed());
+        }
+    }
+
+    let parse = Summary::from_samples(&mut parse_times);
+    Ok(parts
+        .iter()
+        .zip(solve_times.iter_mut())
+        .map(|(&part, times)| BenchResult {
+            day: day.number,
+            part,
+            parse,
+            solve: Summary::from_samples(times),
+        })
+        .collect())
+}
+
+pub fn format_duration(d: Duration) -> String {
+    let ns = d.as_nanos();
+    if ns < 1_000 {
+        format!("{}ns", ns)
+    } else if ns < 1_000_000 {
+        format!("{:.1}µs", ns as f64 / 1e3)
+    } else if ns < 1_000_000_000 {
+        format!("{:.1}ms", ns as f64 / 1e6)
+    } else {
+        format!("{:.2}s", ns as f64 / 1e9)
+    }
+}
+
+fn format_summary(s: &Summary) -> String {
+    format!(
+        "{:>9} {:>9} {:>9}",
+        format_duration(s.min),
+        format_duration(s.median),
+        format_duration(s.max)
+    )
+}
+
+// Baseline files record the median parse and solve times in nanoseconds:
+//   <day> <part> <parse> <solve>
+fn load_baseline(input: &Input) -> Result<HashMap<(u32, usize), Duration>, InputError> {
+    let mut baseline = HashMap::new();
+    for line in input.lines() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 4 {
+            return Err(line.error("expected `<day> <part> <parse ns> <solve ns>`"));
+        }
+        let parse: u64 = line.parse_field(fields[2])?;
+        let solve: u64 = line.parse_field(fields[3])?;
+        baseline.insert(
+            (line.parse_field(fields[0])?, line.parse_field(fields[1])?),
+            Duration::from_nanos(parse + solve),
+        );
+    }
+    Ok(baseline)
+}
+
+fn save_baseline(path: &str, results: &[BenchResult]) -> Result<(), String> {
+    let mut text = String::from("# aoc2021 bench baseline: <day> <part> <parse ns> <solve ns>\n");
+    for r in results {
+        text += &format!(
+            "{} {} {} {}\n",
+            r.day,
+            r.part,
+            r.parse.median.as_nanos(),
+            r.solve.median.as_nanos()
+        );
+    }
+    fs::write(path, text).map_err(|e| format!("could not write {}: {}", path, e))
+}
+
+/// Percentage change from `before` to `after`
+fn change(before: Duration, after: Duration) -> f64 {
+    (after.as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0
+}
+
+pub fn bench(
+    days: &[&Day],
+    parts: &[usize],
+    input: Option<&str>,
+    options: &BenchOptions,
+) -> Result<(), String> {
+    let baseline = match &options.baseline {
+        Some(path) => Some(
+            Input::from_path(path)
+                .and_then(|input| load_baseline(&input))
+                .map_err(|e| e.to_string())?,
+        ),
+        None => None,
+    };
+
+    println!(
+        "{:<15}{:>29}  {:>29}",
+        format!("({} runs)", options.runs),
+        "parse min/median/max",
+        "solve min/median/max"
+    );
+
+    let mut results = vec![];
+    let mut regressions = 0;
+    for day in days {
+        for result in bench_day(day, parts, input, options.runs).map_err(|e| e.to_string())? {
+            let mut line = format!(
+                "Day {:02} part {}  {}  {}",
+                result.day,
+                result.part,
+                format_summary(&result.parse),
+                format_summary(&result.solve)
+            );
+            if let Some(before) = baseline
+                .as_ref()
+                .and_then(|b| b.get(&(result.day, result.part)))
+            {
+                let pct = change(*before, result.total());
+                line += &format!("  {:+.1}%", pct);
+                if pct > options.threshold {
+                    line += " REGRESSION";
+                    regressions += 1;
+                }
+            }
+            println!("{}", line);
+            results.push(result);
+        }
+    }
+
+    if let Some(path) = &options.save_baseline {
+        save_baseline(path, &results)?;
+    }
+    if regressions > 0 {
+        return Err(format!(
+            "{} timing(s) regressed by more than {}%",
+            regressions, options.threshold
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let mut samples: Vec<Duration> = [5, 1, 4, 2, 3].map(Duration::from_millis).to_vec();
+        let s = Summary::from_samples(&mut samples);
+        assert_eq!(s.min, Duration::from_millis(1));
+        assert_eq!(s.median, Duration::from_millis(3));
+        assert_eq!(s.max, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(999)), "999ns");
+        assert_eq!(format_duration(Duration::from_nanos(12_345)), "12.3µs");
+        assert_eq!(format_duration(Duration::from_micros(4_560)), "4.6ms");
+        assert_eq!(format_duration(Duration::from_millis(80_515)), "80.52s");
+    }
+
+    #[test]
+    fn test_baseline() {
+        let input = Input::from_text("# header\n15 1 1000 2000\n15 2 1000 9000\n");
+        let baseline = load_baseline(&input).unwrap();
+        assert_eq!(baseline[&(15, 1)], Duration::from_nanos(3000));
+        assert_eq!(baseline[&(15, 2)], Duration::from_nanos(10000));
+
+        assert!(load_baseline(&Input::from_text("15 1 1000")).is_err());
+    }
+
+    #[test]
+    fn test_change() {
+        let before = Duration::from_millis(100);
+        assert!((change(before, Duration::from_millis(150)) - 50.0).abs() < 1e-9);
+        assert!((change(before, Duration::from_millis(80)) + 20.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,457 @@
+// Command line handling. Nothing fancy - std only, so no clap.
+
+pub const USAGE: &str = "\
+Usage:
+    aoc2021 run <DAY|all> [--part <1|2>] [--input <FILE|->]
+                [--format <text|json>] [--trace <[DAY=]LEVEL>]...
+                [--param <NAME=VALUE>]...
+    aoc2021 bench <DAY|all> [--part <1|2>] [--input <FILE|->] [--runs <N>]
+                  [--baseline <FILE>] [--threshold <PERCENT>]
+                  [--save-baseline <FILE>]
+    aoc2021 sonar [--input <FILE|->] [--window <N>]... [--every <N>]
+    aoc2021 list
+    aoc2021 help
+
+Each day reads inputs/dayNN.txt unless given --input; `-` reads stdin.
+--trace writes what a day is doing to stderr, at LEVEL 1 (least) to 3 (most)
+detail, for every day run or just DAY.
+--param changes one of a day's puzzle parameters (see `list`) from its default.
+
+sonar counts day 1's depth increases as readings arrive (from stdin by
+default), printing the counts so far every N readings (default 1) for each
+window size (default 1 and 3). Lines which aren't a number are skipped.";
+
+#[derive(Debug, PartialEq)]
+pub enum Selection {
+    All,
+    Day(u32),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    Text,
+    /// One JSON object per line, for each day and part run
+    Json,
+}
+
+/// A `--trace` setting: the verbosity for one day, or for every day run
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Trace {
+    pub day: Option<u32>,
+    pub level: u8,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchOptions {
+    pub runs: usize,
+    /// Compare against timings previously saved to this file
+    pub baseline: Option<String>,
+    /// Percentage slowdown (over the baseline) to report as a regression
+    pub threshold: f64,
+    pub save_baseline: Option<String>,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            runs: 10,
+            baseline: None,
+            threshold: 10.0,
+            save_baseline: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SonarOptions {
+    /// File to read, or `-` for stdin
+    pub input: String,
+    pub windows: Vec<usize>,
+    /// Print the counts after every this many readings
+    pub every: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run {
+        selection: Selection,
+        part: Option<usize>,
+        /// Input file to use instead of the day's default, `-` for stdin
+        input: Option<String>,
+        format: Format,
+        trace: Vec<Trace>,
+        /// Parameter overrides, by name
+        params: Vec<(String, i64)>,
+    },
+    Bench {
+        selection: Selection,
+        part: Option<usize>,
+        input: Option<String>,
+        options: BenchOptions,
+    },
+    Sonar(SonarOptions),
+    List,
+    Help,
+}
+
+fn parse_selection(s: &str) -> Result<Selection, String> {
+    if s == "all" {
+        return Ok(Selection::All);
+    }
+    // accept "16", "day16" and "day 16" style references
+    s.trim_start_matches("day")
+        .trim()
+        .parse::<u32>()
+        .map(Selection::Day)
+        .map_err(|_| format!("invalid day `{}`", s))
+}
+
+fn parse_format(s: &str) -> Result<Format, String> {
+    match s {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        _ => Err(format!(
+            "invalid format `{}` (expected `text` or `json`)",
+            s
+        )),
+    }
+}
+
+fn parse_trace(s: &str) -> Result<Trace, String> {
+    let (day, level) = match s.split_once('=') {
+        Some((day, level)) => match parse_selection(day)? {
+            Selection::Day(day) => (Some(day), level),
+            Selection::All => (None, level),
+        },
+        None => (None, s),
+    };
+    let level = parse_value("--trace", level)?;
+    Ok(Trace { day, level })
+}
+
+fn parse_param(s: &str) -> Result<(String, i64), String> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or(format!("invalid parameter `{}` (expected NAME=VALUE)", s))?;
+    Ok((name.to_string(), parse_value("--param", value)?))
+}
+
+fn parse_value<T: std::str::FromStr>(option: &str, s: &str) -> Result<T, String> {
+    s.parse::<T>()
+        .map_err(|_| format!("invalid value `{}` for {}", s, option))
+}
+
+pub fn parse_args<I: Iterator<Item = String>>(args: I) -> Result<Command, String> {
+    // Normalise `--opt=value` into `--opt value` so there's only one form
+    // to deal with below.
+    let mut args = args
+        .flat_map(|arg| match arg.split_once('=') {
+            Some((opt, value)) if opt.starts_with("--") => vec![opt.to_string(), value.to_string()],
+            _ => vec![arg],
+        })
+        .collect::<Vec<String>>()
+        .into_iter();
+
+    let command = match args.next() {
+        Some(c) => c,
+        None => return Ok(Command::Help),
+    };
+
+    match command.as_str() {
+        "run" | "bench" => {
+            let mut selection = None;
+            let mut part = None;
+            let mut input = None;
+            let mut format = Format::Text;
+            let mut trace = vec![];
+            let mut params = vec![];
+            let mut options = BenchOptions::default();
+            let is_bench = command == "bench";
+            while let Some(arg) = args.next() {
+                let mut value = || args.next().ok_or(format!("{} requires a value", arg));
+                match arg.as_str() {
+                    "--part" | "-p" => part = Some(parse_value(&arg, &value()?)?),
+                    "--input" | "-i" => input = Some(value()?),
+                    "--format" if !is_bench => format = parse_format(&value()?)?,
+                    "--trace" if !is_bench => trace.push(parse_trace(&value()?)?),
+                    "--param" if !is_bench => params.push(parse_param(&value()?)?),
+                    "--runs" if is_bench => options.runs = parse_value(&arg, &value()?)?,
+                    "--baseline" if is_bench => options.baseline = Some(value()?),
+                    "--threshold" if is_bench => options.threshold = parse_value(&arg, &value()?)?,
+                    "--save-baseline" if is_bench => options.save_baseline = Some(value()?),
+                    _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+                    _ if selection.is_none() => selection = Some(parse_selection(&arg)?),
+                    _ => return Err(format!("unexpected argument `{}`", arg)),
+                }
+            }
+            let selection =
+                selection.ok_or(format!("{} requires a day number or `all`", command))?;
+            if input.is_some() && selection == Selection::All {
+                return Err("--input needs a single day, not `all`".to_string());
+            }
+            if is_bench {
+                if options.runs == 0 {
+                    return Err("--runs must be at least 1".to_string());
+                }
+                Ok(Command::Bench {
+                    selection,
+                    part,
+                    input,
+                    options,
+                })
+            } else {
+                Ok(Command::Run {
+                    selection,
+                    part,
+                    input,
+                    format,
+                    trace,
+                    params,
+                })
+            }
+        }
+        "sonar" => {
+            let mut input = "-".to_string();
+            let mut windows = vec![];
+            let mut every = 1;
+            while let Some(arg) = args.next() {
+                let mut value = || args.next().ok_or(format!("{} requires a value", arg));
+                match arg.as_str() {
+                    "--input" | "-i" => input = value()?,
+                    "--window" => windows.push(parse_value(&arg, &value()?)?),
+                    "--every" => every = parse_value(&arg, &value()?)?,
+                    _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+                    _ => return Err(format!("unexpected argument `{}`", arg)),
+                }
+            }
+            if windows.is_empty() {
+                windows = vec![1, 3];
+            }
+            if windows.contains(&0) || every == 0 {
+                return Err("--window and --every must be at least 1".to_string());
+            }
+            Ok(Command::Sonar(SonarOptions {
+                input,
+                windows,
+                every,
+            }))
+        }
+        "list" => Ok(Command::List),
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        other => Err(format!("unknown command `{}`", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<Command, String> {
+        parse_args(s.split_whitespace().map(|x| x.to_string()))
+    }
+
+    #[test]
+    fn test_run_day_part() {
+        assert_eq!(
+            parse("run 16 --part 2"),
+            Ok(Command::Run {
+                selection: Selection::Day(16),
+                part: Some(2),
+                input: None,
+                format: Format::Text,
+                trace: vec![],
+                params: vec![],
+            })
+        );
+        assert_eq!(
+            parse("run day05 --part=1"),
+            Ok(Command::Run {
+                selection: Selection::Day(5),
+                part: Some(1),
+                input: None,
+                format: Format::Text,
+                trace: vec![],
+                params: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!(parse_selection("16"), Ok(Selection::Day(16)));
+        assert_eq!(parse_selection("day16"), Ok(Selection::Day(16)));
+        assert_eq!(parse_selection("day 16"), Ok(Selection::Day(16)));
+        assert_eq!(parse_selection("all"), Ok(Selection::All));
+        assert!(parse_selection("day").is_err());
+    }
+
+    #[test]
+    fn test_run_input() {
+        assert_eq!(
+            parse("run 7 --input example.txt"),
+            Ok(Command::Run {
+                selection: Selection::Day(7),
+                part: None,
+                input: Some("example.txt".to_string()),
+                format: Format::Text,
+                trace: vec![],
+                params: vec![],
+            })
+        );
+        assert_eq!(
+            parse("run 7 -p 1 -i -"),
+            Ok(Command::Run {
+                selection: Selection::Day(7),
+                part: Some(1),
+                input: Some("-".to_string()),
+                format: Format::Text,
+                trace: vec![],
+                params: vec![],
+            })
+        );
+        assert!(parse("run all --input example.txt").is_err());
+        assert!(parse("run 7 --input").is_err());
+    }
+
+    #[test]
+    fn test_run_all() {
+        assert_eq!(
+            parse("run all"),
+            Ok(Command::Run {
+                selection: Selection::All,
+                part: None,
+                input: None,
+                format: Format::Text,
+                trace: vec![],
+                params: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn test_run_format() {
+        assert_eq!(
+            parse("run all --format json"),
+            Ok(Command::Run {
+                selection: Selection::All,
+                part: None,
+                input: None,
+                format: Format::Json,
+                trace: vec![],
+                params: vec![],
+            })
+        );
+        assert!(parse("run all --format xml").is_err());
+        assert!(parse("bench all --format json").is_err());
+    }
+
+    #[test]
+    fn test_run_trace() {
+        assert_eq!(
+            parse("run all --trace 2 --trace day22=3 --trace=19=1"),
+            Ok(Command::Run {
+                selection: Selection::All,
+                part: None,
+                input: None,
+                format: Format::Text,
+                trace: vec![
+                    Trace {
+                        day: None,
+                        level: 2
+                    },
+                    Trace {
+                        day: Some(22),
+                        level: 3
+                    },
+                    Trace {
+                        day: Some(19),
+                        level: 1
+                    },
+                ],
+                params: vec![],
+            })
+        );
+        assert!(parse("run 12 --trace loud").is_err());
+        assert!(parse("run 12 --trace 12=").is_err());
+        assert!(parse("bench 12 --trace 1").is_err());
+    }
+
+    #[test]
+    fn test_run_param() {
+        assert_eq!(
+            parse("run 6 --param days1=18 --param=days2=0"),
+            Ok(Command::Run {
+                selection: Selection::Day(6),
+                part: None,
+                input: None,
+                format: Format::Text,
+                trace: vec![],
+                params: vec![("days1".to_string(), 18), ("days2".to_string(), 0)],
+            })
+        );
+        assert!(parse("run 6 --param days1").is_err());
+        assert!(parse("run 6 --param days1=lots").is_err());
+        assert!(parse("bench 6 --param days1=18").is_err());
+    }
+
+    #[test]
+    fn test_bench() {
+        assert_eq!(
+            parse("bench all"),
+            Ok(Command::Bench {
+                selection: Selection::All,
+                part: None,
+                input: None,
+                options: BenchOptions::default(),
+            })
+        );
+        assert_eq!(
+            parse("bench 15 --part 2 --runs=3 --baseline base.txt --threshold 25"),
+            Ok(Command::Bench {
+                selection: Selection::Day(15),
+                part: Some(2),
+                input: None,
+                options: BenchOptions {
+                    runs: 3,
+                    baseline: Some("base.txt".to_string()),
+                    threshold: 25.0,
+                    save_baseline: None,
+                },
+            })
+        );
+        assert!(parse("bench 15 --runs 0").is_err());
+        assert!(parse("run 15 --runs 3").is_err());
+    }
+
+    #[test]
+    fn test_sonar() {
+        assert_eq!(
+            parse("sonar"),
+            Ok(Command::Sonar(SonarOptions {
+                input: "-".to_string(),
+                windows: vec![1, 3],
+                every: 1,
+            }))
+        );
+        assert_eq!(
+            parse("sonar --input depths.txt --window 5 --window=10 --every 1000"),
+            Ok(Command::Sonar(SonarOptions {
+                input: "depths.txt".to_string(),
+                windows: vec![5, 10],
+                every: 1000,
+            }))
+        );
+        assert!(parse("sonar --window 0").is_err());
+        assert!(parse("sonar --every 0").is_err());
+        assert!(parse("sonar 1").is_err());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(parse("run").is_err());
+        assert!(parse("run sixteen").is_err());
+        assert!(parse("run 16 --part").is_err());
+        assert!(parse("run 16 17").is_err());
+        assert!(parse("frobnicate").is_err());
+    }
+}
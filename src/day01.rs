@@ -1,51 +1,300 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader, Error},
+use std::{collections::VecDeque, io::BufRead, iter};
+
+use crate::{
+    input::{ErrorKind, Input, InputError, LineReader},
+    params::{Param, Params},
+    solution::{Answer, Solution},
+    trace,
 };
 
-pub fn read_int_list(filename: &str) -> Result<Vec<i32>, Error> {
-    let f = File::open(filename)?;
-    Ok(BufReader::new(f)
-        .lines()
-        .map(|l| l.expect("Err"))
-        .map(|l| l.parse::<i32>().unwrap())
-        .collect())
-}
-
-pub fn step1() {
-    let mut count = 0;
-    let mut current = None;
-    for value in read_int_list("inputs/day01.txt").unwrap() {
-        if let Some(old_val) = current {
-            if old_val < value {
-                count += 1;
+pub const PARAMS: &[Param] = &[
+    Param {
+        name: "window1",
+        default: 1,
+        min: 1,
+        max: 10_000,
+        help: "readings summed in each window in part 1",
+    },
+    Param {
+        name: "window2",
+        default: 3,
+        min: 1,
+        max: 10_000,
+        help: "readings summed in each window in part 2",
+    },
+];
+
+/// How one window's total depth compares to the window before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change {
+    /// Which window, counting the first full one as 0
+    pub position: usize,
+    pub delta: i64,
+}
+
+/// The latest readings, just enough to see how each new one changes the
+/// sum of the last `width`. Neighbouring windows share all but their first
+/// and last readings, so each change is the newest reading minus the one
+/// which dropped out.
+#[derive(Debug, Clone)]
+pub struct Window {
+    width: usize,
+    readings: VecDeque<i32>,
+    position: usize,
+}
+
+impl Window {
+    pub fn new(width: usize) -> Self {
+        assert!(width > 0, "windows need at least one reading");
+        Self {
+            width,
+            readings: VecDeque::with_capacity(width + 1),
+            position: 0,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Add the next reading, returning how the window changed (unless it
+    /// has only just filled up).
+    pub fn push(&mut self, reading: i32) -> Option<Change> {
+        self.readings.push_back(reading);
+        if self.readings.len() <= self.width {
+            return None;
+        }
+        let oldest = self.readings.pop_front().expect("window is full");
+        self.position += 1;
+        Some(Change {
+            position: self.position,
+            delta: reading as i64 - oldest as i64,
+        })
+    }
+}
+
+/// The changes between successive windows of readings.
+pub struct WindowChanges<I> {
+    readings: I,
+    window: Window,
+}
+
+impl<I: Iterator<Item = i32>> Iterator for WindowChanges<I> {
+    type Item = Change;
+
+    fn next(&mut self) -> Option<Change> {
+        loop {
+            if let Some(change) = self.window.push(self.readings.next()?) {
+                return Some(change);
             }
         }
-        current = Some(value);
     }
-    println!("Increment count: {}", count);
 }
 
-pub fn step2() {
-    let mut count = 0;
-    let mut previous = 0;
-    let mut current;
-    let mut window: Vec<i32> = vec![];
-    for value in read_int_list("inputs/day01.txt").unwrap() {
-        if window.len() < 3 {
-            window.push(value);
-            continue;
-        } else {
-            window.remove(0); // fine for a 3-element list
-            window.push(value);
+/// Changes between the sums of each `width` consecutive readings.
+pub fn window_changes<I: IntoIterator<Item = i32>>(
+    readings: I,
+    width: usize,
+) -> WindowChanges<I::IntoIter> {
+    WindowChanges {
+        readings: readings.into_iter(),
+        window: Window::new(width),
+    }
+}
+
+/// Readings from a stream, one per line, as they arrive. Blank lines are
+/// ignored, and any others which aren't a number (or aren't even text) go
+/// to `skipped` rather than stopping everything. Only a failure to read
+/// the stream at all ends it early (after going to `skipped` as well).
+pub fn stream_readings<R: BufRead>(
+    mut lines: LineReader<R>,
+    mut skipped: impl FnMut(InputError),
+) -> impl Iterator<Item = i32> {
+    iter::from_fn(move || loop {
+        match lines.next_line()? {
+            Ok(line) if line.trim().is_empty() => continue,
+            Ok(line) => match line.parse() {
+                Ok(reading) => return Some(reading),
+                Err(e) => skipped(e),
+            },
+            Err(e) => {
+                let fatal = matches!(e.kind, ErrorKind::Io(_));
+                skipped(e);
+                if fatal {
+                    return None;
+                }
+            }
         }
-        assert!(window.len() == 3);
-        current = window.iter().sum();
-        if current > previous {
-            count += 1;
+    })
+}
+
+/// What a sweep's window changes add up to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SweepStats {
+    /// Windows deeper than the one before
+    pub increases: usize,
+    /// Most increases in a row
+    pub longest_run: usize,
+    /// The biggest change from one window to the next (which could be
+    /// negative, if there are no increases)
+    pub largest_increase: Option<i64>,
+    /// The position of each increase, only kept if asked for with
+    /// `with_positions` as (unlike the rest) it grows with the sweep
+    pub increase_positions: Option<Vec<usize>>,
+    // increases in a row up to the latest change
+    run: usize,
+}
+
+impl SweepStats {
+    pub fn with_positions() -> Self {
+        Self {
+            increase_positions: Some(vec![]),
+            ..Self::default()
+        }
+    }
+
+    pub fn add(&mut self, change: Change) {
+        if change.delta > 0 {
+            self.increases += 1;
+            self.run += 1;
+            self.longest_run = self.longest_run.max(self.run);
+            if let Some(positions) = &mut self.increase_positions {
+                positions.push(change.position);
+            }
+        } else {
+            self.run = 0;
         }
-        previous = current;
+        self.largest_increase = self.largest_increase.max(Some(change.delta));
+    }
+}
+
+impl Extend<Change> for SweepStats {
+    fn extend<T: IntoIterator<Item = Change>>(&mut self, changes: T) {
+        changes.into_iter().for_each(|change| self.add(change));
+    }
+}
+
+impl FromIterator<Change> for SweepStats {
+    fn from_iter<T: IntoIterator<Item = Change>>(changes: T) -> Self {
+        let mut stats = Self::default();
+        stats.extend(changes);
+        stats
+    }
+}
+
+pub struct SonarSweep {
+    depths: Vec<i32>,
+
+    params: Params,
+}
+
+impl SonarSweep {
+    pub fn stats(&self, width: usize) -> SweepStats {
+        let mut stats = SweepStats::with_positions();
+        stats.extend(window_changes(self.depths.iter().copied(), width));
+        trace!(
+            1,
+            "window {}: {} increases, longest run {}, largest increase {:?}",
+            width,
+            stats.increases,
+            stats.longest_run,
+            stats.largest_increase
+        );
+        trace!(2, "increases at {:?}", stats.increase_positions);
+        stats
+    }
+}
+
+impl Solution for SonarSweep {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        Ok(Self {
+            depths: input.parse_lines()?,
+            params: Params::new(PARAMS),
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        self.stats(self.params.get("window1") as usize)
+            .increases
+            .into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.stats(self.params.get("window2") as usize)
+            .increases
+            .into()
+    }
+
+    fn configure(&mut self, params: &Params) {
+        self.params = params.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
+
+    #[test]
+    fn test_example() {
+        let sweep: SonarSweep = example(1);
+        assert_eq!(sweep.part1(), 7.into());
+        assert_eq!(sweep.part2(), 5.into());
+    }
+
+    #[test]
+    fn test_stats() {
+        let sweep: SonarSweep = example(1);
+        let stats = sweep.stats(1);
+        assert_eq!(stats.increase_positions, Some(vec![1, 2, 3, 5, 6, 7, 9]));
+        assert_eq!(stats.longest_run, 3);
+        assert_eq!(stats.largest_increase, Some(33));
+
+        // window sums 607, 618, 618, 617, 647, 716, 769, 792
+        let stats = sweep.stats(3);
+        assert_eq!(stats.increase_positions, Some(vec![1, 4, 5, 6, 7]));
+        assert_eq!(stats.longest_run, 4);
+        assert_eq!(stats.largest_increase, Some(69));
+
+        // too few readings for more than one window
+        assert_eq!(sweep.stats(10), SweepStats::with_positions());
+    }
+
+    #[test]
+    fn test_stream_readings() {
+        let text = "199\n200\n\nabc\n208\n2 10\n";
+        let lines = LineReader::new("<pipe>", text.as_bytes());
+        let mut skipped = vec![];
+        let readings: Vec<i32> = stream_readings(lines, |e| skipped.push(e.to_string())).collect();
+        assert_eq!(readings, vec![199, 200, 208]);
+        assert_eq!(
+            skipped,
+            vec![
+                "<pipe>: line 4, col 1: invalid value: `abc`",
+                "<pipe>: line 6, col 1: invalid value: `2 10`"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stream_bad_text() {
+        let text = b"199\n\xff\n200\n";
+        let lines = LineReader::new("<pipe>", &text[..]);
+        let mut skipped = vec![];
+        let readings: Vec<i32> = stream_readings(lines, |e| skipped.push(e.to_string())).collect();
+        assert_eq!(readings, vec![199, 200]);
+        assert_eq!(skipped, vec!["<pipe>: line 2: not valid UTF-8"]);
+    }
+
+    #[test]
+    fn test_window_changes() {
+        let changes: Vec<i64> = window_changes([5, 1, 4, 2, 8], 2)
+            .map(|c| c.delta)
+            .collect();
+        assert_eq!(changes, vec![-1, 1, 4]);
+        // works on anything, however long
+        let stats: SweepStats = window_changes((0..1_000_000).map(|n| n % 7), 3).collect();
+        assert_eq!(stats.longest_run, 4);
+        assert_eq!(stats.increase_positions, None);
     }
-    count -= 1; // because we shouldn't count the first 0->anything transition
-    println!("Windowed Increment count: {}", count);
 }
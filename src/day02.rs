@@ -1,50 +1,324 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+use std::{fmt, io};
+
+use crate::{
+    input::{Cursor, Input, InputError, ParseError},
+    solution::{Answer, Solution},
+    trace,
 };
 
-pub fn read_list(filename: &str) -> Vec<String> {
-    let f = File::open(filename).expect("Could not read file");
-    BufReader::new(f)
-        .lines()
-        .map(|l| l.expect("Err"))
-        .collect()
-}
-
-pub fn step1() {
-    let mut xpos = 0;
-    let mut depth = 0;
-    for instr in read_list("inputs/day02.txt") {
-        let mut instr_iter = instr.split_whitespace();
-        let movement = instr_iter.next().unwrap();
-        let amount: i32 = instr_iter.next().unwrap().parse().unwrap();
-        match movement {
-            "up" => depth -= amount,
-            "down" => depth += amount,
-            "forward" => xpos += amount,
-            _ => ()
-        }
-    }
-    println!("xpos * depth = {}", xpos * depth);
-}
-
-pub fn step2() {
-    let mut xpos = 0;
-    let mut depth = 0;
-    let mut aim = 0;
-    for instr in read_list("inputs/day02.txt") {
-        let mut instr_iter = instr.split_whitespace();
-        let movement = instr_iter.next().unwrap();
-        let amount: i32 = instr_iter.next().unwrap().parse().unwrap();
-        match movement {
-            "up" => aim -= amount,
-            "down" => aim += amount,
-            "forward" => {
-                xpos += amount;
-                depth += aim * amount;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Forward(i64),
+    Down(i64),
+    Up(i64),
+}
+
+impl Command {
+    fn name(&self) -> &'static str {
+        match self {
+            Command::Forward(_) => "forward",
+            Command::Down(_) => "down",
+            Command::Up(_) => "up",
+        }
+    }
+
+    fn amount(&self) -> i64 {
+        match *self {
+            Command::Forward(n) | Command::Down(n) | Command::Up(n) => n,
+        }
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.name(), self.amount())
+    }
+}
+
+/// Read a command like `forward 5`.
+fn read_command(c: &mut Cursor) -> Result<Command, ParseError> {
+    let start = c.clone();
+    let command: fn(i64) -> Command = match c.word() {
+        "forward" => Command::Forward,
+        "down" => Command::Down,
+        "up" => Command::Up,
+        name => return Err(start.error(&format!("unknown command `{}`", name))),
+    };
+    c.expect(" ")?;
+    Ok(command(c.number()?))
+}
+
+/// Where the submarine is, and which way it's pointing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct State {
+    pub position: i64,
+    pub depth: i64,
+    pub aim: i64,
+}
+
+/// How the submarine responds to commands.
+pub trait Movement {
+    fn apply(&self, state: &mut State, command: Command);
+}
+
+/// What you'd expect: up and down change the depth directly.
+#[derive(Debug, Clone, Copy)]
+pub struct Simple;
+
+impl Movement for Simple {
+    fn apply(&self, state: &mut State, command: Command) {
+        match command {
+            Command::Forward(n) => state.position += n,
+            Command::Down(n) => state.depth += n,
+            Command::Up(n) => state.depth -= n,
+        }
+    }
+}
+
+/// What the manual says: up and down tilt the submarine, and going forward
+/// changes the depth by how far it's tilted.
+#[derive(Debug, Clone, Copy)]
+pub struct Aimed;
+
+impl Movement for Aimed {
+    fn apply(&self, state: &mut State, command: Command) {
+        match command {
+            Command::Forward(n) => {
+                state.position += n;
+                state.depth += state.aim * n;
             }
-            _ => ()
+            Command::Down(n) => state.aim += n,
+            Command::Up(n) => state.aim -= n,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Submarine<M> {
+    model: M,
+    state: State,
+}
+
+impl<M: Movement> Submarine<M> {
+    pub fn new(model: M) -> Self {
+        Self {
+            model,
+            state: State::default(),
+        }
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn execute(&mut self, command: Command) {
+        self.model.apply(&mut self.state, command);
+    }
+
+    pub fn run(&mut self, commands: impl IntoIterator<Item = Command>) {
+        commands
+            .into_iter()
+            .for_each(|command| self.execute(command));
+    }
+
+    /// Execute `commands` one at a time as the result is iterated over,
+    /// giving the state after each.
+    pub fn steps<'a>(
+        &'a mut self,
+        commands: impl IntoIterator<Item = Command> + 'a,
+    ) -> impl Iterator<Item = Step> + 'a {
+        commands.into_iter().map(|command| {
+            self.execute(command);
+            Step {
+                command,
+                state: self.state,
+            }
+        })
+    }
+}
+
+/// A command, and where it left the submarine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub command: Command,
+    pub state: State,
+}
+
+/// Every step of a course, in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trajectory {
+    steps: Vec<Step>,
+}
+
+impl Trajectory {
+    pub fn iter(&self) -> impl Iterator<Item = &Step> {
+        self.steps.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn max_depth(&self) -> Option<i64> {
+        self.steps.iter().map(|step| step.state.depth).max()
+    }
+
+    /// The first step which takes the submarine deeper than `depth`, and
+    /// its number (counting from 1, as `write_csv` does).
+    pub fn first_deeper_than(&self, depth: i64) -> Option<(usize, &Step)> {
+        self.steps
+            .iter()
+            .enumerate()
+            .map(|(idx, step)| (idx + 1, step))
+            .find(|(_, step)| step.state.depth > depth)
+    }
+
+    /// Write as CSV, with a header line and then a line per step, numbered
+    /// from 1.
+    pub fn write_csv(&self, out: &mut impl io::Write) -> io::Result<()> {
+        writeln!(out, "step,command,amount,position,depth,aim")?;
+        for (idx, step) in self.steps.iter().enumerate() {
+            writeln!(
+                out,
+                "{},{},{},{},{},{}",
+                idx + 1,
+                step.command.name(),
+                step.command.amount(),
+                step.state.position,
+                step.state.depth,
+                step.state.aim
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl FromIterator<Step> for Trajectory {
+    fn from_iter<T: IntoIterator<Item = Step>>(steps: T) -> Self {
+        Self {
+            steps: steps.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Trajectory {
+    type Item = &'a Step;
+    type IntoIter = std::slice::Iter<'a, Step>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.steps.iter()
+    }
+}
+
+#[derive(Debug)]
+pub struct Dive {
+    commands: Vec<Command>,
+}
+
+impl Dive {
+    pub fn trajectory(&self, model: impl Movement) -> Trajectory {
+        Submarine::new(model)
+            .steps(self.commands.iter().copied())
+            .collect()
+    }
+
+    // Where the planned course ends up, as the puzzle answer wants it
+    fn course(&self, model: impl Movement) -> i64 {
+        let trajectory = self.trajectory(model);
+        trace!(1, "max depth {:?}", trajectory.max_depth());
+        for step in &trajectory {
+            trace!(2, "{}: {:?}", step.command, step.state);
         }
+        let end = trajectory
+            .iter()
+            .last()
+            .map_or(State::default(), |step| step.state);
+        end.position * end.depth
+    }
+}
+
+impl Solution for Dive {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        Ok(Self {
+            commands: input
+                .lines()
+                .map(|line| line.parse_with(read_command))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        self.course(Simple).into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.course(Aimed).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
+
+    #[test]
+    fn test_example() {
+        let dive: Dive = example(2);
+        assert_eq!(dive.part1(), 150.into());
+        assert_eq!(dive.part2(), 900.into());
+    }
+
+    #[test]
+    fn test_submarine() {
+        let mut sub = Submarine::new(Aimed);
+        sub.run([Command::Down(2), Command::Forward(3), Command::Up(5)]);
+        let expected = State {
+            position: 3,
+            depth: 6,
+            aim: -3,
+        };
+        assert_eq!(sub.state(), expected);
+    }
+
+    #[test]
+    fn test_trajectory() {
+        let dive: Dive = example(2);
+        let trajectory = dive.trajectory(Aimed);
+        assert_eq!(trajectory.len(), 6);
+        assert_eq!(trajectory.max_depth(), Some(60));
+        let (number, step) = trajectory.first_deeper_than(30).unwrap();
+        assert_eq!((number, step.command), (3, Command::Forward(8)));
+        assert_eq!(step.state.depth, 40);
+        assert!(trajectory.first_deeper_than(60).is_none());
+
+        let mut csv = vec![];
+        trajectory.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0], "step,command,amount,position,depth,aim");
+        assert_eq!(lines[3], "3,forward,8,13,40,5");
+        assert_eq!(lines[6], "6,forward,2,15,60,10");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Dive::parse(&Input::from_text("forward 5\nsideways 3")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 2, col 1: unknown command `sideways`"
+        );
+        // not just `up` followed by something else
+        let err = Dive::parse(&Input::from_text("upward 3")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 1, col 1: unknown command `upward`"
+        );
+        let err = Dive::parse(&Input::from_text("down five")).unwrap_err();
+        assert_eq!(err.to_string(), "<input>: line 1, col 6: expected number");
     }
-    println!("xpos * depth = {}", xpos * depth);
 }
@@ -1,111 +1,168 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+use crate::{
+    input::{Input, InputError},
+    solution::{Answer, Solution},
+    trace,
 };
 
-pub fn read_list(filename: &str) -> Vec<String> {
-    let f = File::open(filename).expect("Could not read file");
-    BufReader::new(f)
-        .lines()
-        .map(|l| l.expect("Err"))
-        .collect()
+#[derive(Debug)]
+pub struct Diagnostic {
+    readings: Vec<u64>,
+    // bits in each reading, all the same
+    width: usize,
 }
 
-pub fn step1() {
-    let mut gamma = 0;
-    let mut epsilon = 0;
-    let mut one_count = [0; 12];
-    let mut zero_count = [0; 12];
-    for reading in read_list("inputs/day03.txt") {
-        for idx in 0..12 {
-            match reading.as_str().chars().nth(idx) {
-                Some('1') => one_count[idx] += 1,
-                Some('0') => zero_count[idx] += 1,
-                _ => ()
+impl Diagnostic {
+    fn ones(readings: &[u64], bit: usize) -> usize {
+        readings.iter().filter(|&&r| r >> bit & 1 == 1).count()
+    }
+
+    // Is 1 the most common value (or as common as 0) for this bit?
+    fn mostly_ones(readings: &[u64], bit: usize) -> bool {
+        Self::ones(readings, bit) * 2 >= readings.len()
+    }
+
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.width)
+    }
+
+    fn power_consumption(&self) -> (u64, u64) {
+        let gamma = (0..self.width)
+            .filter(|&bit| Self::ones(&self.readings, bit) * 2 > self.readings.len())
+            .fold(0, |gamma, bit| gamma | 1 << bit);
+        let epsilon = !gamma & self.mask();
+        trace!(1, "gamma: {}, epsilon: {}", gamma, epsilon);
+        (gamma, epsilon)
+    }
+
+    // Narrow the readings down bit by bit, from the top, keeping those
+    // where the bit matches what `keep` picks given whether 1 is the most
+    // common value, until only one is left. Bits where the readings all
+    // agree are skipped, as otherwise picking the least common value would
+    // leave none - so with duplicates this can end with several readings,
+    // all the same.
+    fn rating(&self, keep: impl Fn(bool) -> bool) -> Result<u64, String> {
+        let mut readings = self.readings.clone();
+        for bit in (0..self.width).rev() {
+            if readings.len() == 1 {
+                break;
+            }
+            let ones = Self::ones(&readings, bit);
+            if ones == 0 || ones == readings.len() {
+                continue;
             }
+            let value = u64::from(keep(Self::mostly_ones(&readings, bit)));
+            readings.retain(|r| r >> bit & 1 == value);
+            trace!(2, "bit {}: {} remain", bit, readings.len());
         }
+        readings
+            .first()
+            .copied()
+            .ok_or_else(|| "no readings left to rate".to_string())
     }
-    println!("one_count: {:?}", one_count);
-    println!("zero_count: {:?}", zero_count);
-    for idx in 0..12 {
-        let bit = 11 - idx;
-        if one_count[idx] > zero_count[idx] {
-            gamma |= 1 << bit;
-        } else {
-            epsilon |= 1 << bit;
-        }
+
+    fn life_support_rating(&self) -> Result<(u64, u64), String> {
+        let o2 = self.rating(|mostly_ones| mostly_ones)?;
+        let co2 = self.rating(|mostly_ones| !mostly_ones)?;
+        trace!(1, "o2: {}, co2: {}", o2, co2);
+        Ok((o2, co2))
     }
-    println!("gamma: {:?}", gamma);
-    println!("epsilon: {:?}", epsilon);
-    println!("epsilon * gamma = {}", gamma * epsilon);
 }
 
-fn count_v<T: PartialEq>(i: &Vec<Vec<T>>, bit_pos: usize, value: T) -> usize {
-    let mut c = 0;
-    for x in i.iter() {
-        if x[bit_pos] == value {
-            c += 1;
+impl Solution for Diagnostic {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let mut readings = vec![];
+        let mut width = 0;
+        for line in input.lines() {
+            let bits = line.trim_end();
+            if readings.is_empty() {
+                width = bits.len();
+                if !(1..=64).contains(&width) {
+                    return Err(line.error("readings must have 1 to 64 bits"));
+                }
+            } else if bits.len() != width {
+                return Err(line.field_error(&format!("expected {} bits", width), bits));
+            }
+            let mut reading = 0;
+            for (idx, ch) in bits.char_indices() {
+                reading = reading << 1
+                    | match ch {
+                        '0' => 0,
+                        '1' => 1,
+                        _ => {
+                            let ch = &bits[idx..idx + ch.len_utf8()];
+                            return Err(line.field_error("expected `0` or `1`", ch));
+                        }
+                    };
+            }
+            readings.push(reading);
         }
+        if readings.is_empty() {
+            return Err(input.error("no readings"));
+        }
+        Ok(Self { readings, width })
+    }
+
+    fn part1(&self) -> Answer {
+        let (gamma, epsilon) = self.power_consumption();
+        // with up to 64 bits each, this can be too big for a u64
+        (gamma as i128 * epsilon as i128).into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.life_support_rating()
+            .map(|(o2, co2)| o2 as i128 * co2 as i128)
+            .into()
     }
-    c
-    //i.iter().filter(|&x| *x == value).count()
 }
 
-pub fn step2() {
-    let mut o2_rating = 0;
-    let mut co2_rating = 0;
-
-    // Part 2
-    let mut o2_readings: Vec<Vec<_>>  = read_list("inputs/day03.txt").iter()
-        .map(|l| l.as_str().chars().collect())
-        .collect();
-
-    //let mut co2_readings = o2_readings.clone();
-    let mut co2_readings: Vec<Vec<_>>  = read_list("inputs/day03.txt").iter()
-        .map(|l| l.as_str().chars().collect())
-        .collect();
-
-    let mut test_pos = 0;
-    while o2_readings.len() > 1 && test_pos < 12 {
-        // annoyance: why aren't vectors their own iterators?
-        // e.g. iter/collect would be nice if inferred...
-        //o2_readings = o2_readings.iter().filter(|r| criteria(r[test_pos]));
-        //
-        // annoyance: why can't I pull this closure out of the loop as a var?
-        let mut target = '1';
-        if count_v(&o2_readings, test_pos, '0') > count_v(&o2_readings, test_pos, '1') {
-            target = '0';
-        }
-        o2_readings.retain(|x| x[test_pos] == target);
-        test_pos += 1;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
+
+    #[test]
+    fn test_example() {
+        let diagnostic: Diagnostic = example(3);
+        assert_eq!(diagnostic.width, 5);
+        assert_eq!(diagnostic.power_consumption(), (22, 9));
+        assert_eq!(diagnostic.life_support_rating(), Ok((23, 10)));
+        assert_eq!(diagnostic.part1(), 198.into());
+        assert_eq!(diagnostic.part2(), 230.into());
     }
 
-    test_pos = 0;
-    while co2_readings.len() > 1 && test_pos < 12 {
-        // annoyance: why aren't vectors their own iterators?
-        // e.g. iter/collect would be nice if inferred...
-        //o2_readings = o2_readings.iter().filter(|r| criteria(r[test_pos]));
-        //
-        // annoyance: why can't I pull this closure out of the loop as a var?
-        let mut target = '0';
-        if count_v(&co2_readings, test_pos, '0') > count_v(&co2_readings, test_pos, '1') {
-            target = '1';
-        }
-        co2_readings.retain(|x| x[test_pos] == target);
-        test_pos += 1;
-        println!("Remain: {}", co2_readings.len());
+    #[test]
+    fn test_parse_errors() {
+        let err = Diagnostic::parse(&Input::from_text("00100\n1111\n")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 2, col 1: expected 5 bits: `1111`"
+        );
+        let err = Diagnostic::parse(&Input::from_text("00100\n11201\n")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 2, col 3: expected `0` or `1`: `2`"
+        );
+        assert!(Diagnostic::parse(&Input::from_text("")).is_err());
+        assert!(Diagnostic::parse(&Input::from_text(&"1".repeat(65))).is_err());
     }
 
-    for idx in 0..12 {
-        let bit = 11 - idx;
-        if o2_readings[0][idx] == '1' {
-            o2_rating |= 1 << bit;
-        }
-        if co2_readings[0][idx] == '1' {
-            co2_rating |= 1 << bit;
-        }
+    #[test]
+    fn test_wide() {
+        let all_ones = "1".repeat(64);
+        let input = format!("{}\n{}\n{}\n", all_ones, all_ones, "0".repeat(64));
+        let diagnostic = Diagnostic::parse(&Input::from_text(&input)).unwrap();
+        assert_eq!(diagnostic.power_consumption(), (u64::MAX, 0));
+        assert_eq!(diagnostic.life_support_rating(), Ok((u64::MAX, 0)));
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let diagnostic = Diagnostic::parse(&Input::from_text("10\n10\n")).unwrap();
+        assert_eq!(diagnostic.life_support_rating(), Ok((2, 2)));
+        assert_eq!(diagnostic.part2(), 4.into());
+
+        let input = "0110\n0110\n1000\n1001\n";
+        let diagnostic = Diagnostic::parse(&Input::from_text(input)).unwrap();
+        assert_eq!(diagnostic.life_support_rating(), Ok((9, 6)));
     }
-    println!("{:?}", o2_readings);
-    println!("{:?}", co2_readings);
-    println!("{}. {}. {}", o2_rating, co2_rating, o2_rating * co2_rating);
 }
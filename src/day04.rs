@@ -1,14 +1,10 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+use crate::{
+    input::{Input, InputError},
+    solution::{Answer, Solution},
+    trace,
 };
 
-pub fn read_list(filename: &str) -> Vec<String> {
-    let f = File::open(filename).expect("Could not read file");
-    BufReader::new(f).lines().map(|l| l.expect("Err")).collect()
-}
-
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct BingoBoard {
     numbers: Vec<i32>,
     // marked is a bitmap - bit 0 is the first (top-left) number
@@ -61,63 +57,39 @@ impl BingoBoard {
     }
 }
 
-struct Game {
+#[derive(Clone)]
+pub struct Game {
     boards: Vec<BingoBoard>,
     sequence: Vec<i32>,
 }
 
 impl Game {
-    fn new(filename: &str) -> Self {
-        let mut boards = vec![];
-        let mut sequence = vec![];
-        let mut board: Vec<i32> = vec![];
-        for (idx, line) in read_list(filename).iter().enumerate() {
-            if idx == 0 {
-                sequence = line.split(',').map(|x| x.parse::<i32>().unwrap()).collect();
-            } else {
-                if line.len() == 0 {
-                    continue;
-                }
-                board.extend::<Vec<i32>>(
-                    line.split_whitespace()
-                        .map(|x| x.parse::<i32>().unwrap())
-                        .collect(),
-                );
-                if board.len() == 25 {
-                    boards.push(BingoBoard::new(board.clone()));
-                    board.clear();
-                }
-            }
-        }
-        Game { boards, sequence }
-    }
-
-    fn play(&mut self) -> i32 {
+    // The first winner's score, if anyone wins.
+    fn play(&mut self) -> Option<i32> {
         for call in &self.sequence {
-            println!("Calling {}", call);
+            trace!(2, "Calling {}", call);
             for board in &mut self.boards {
                 board.called(*call);
                 if board.complete() {
-                    println!("Bingo!!");
-                    println!("{:?}", board);
-                    return board.score(*call);
+                    trace!(1, "Bingo!! {:?}", board);
+                    return Some(board.score(*call));
                 }
             }
         }
-        -1
+        None
     }
 
-    fn play_to_lose(&mut self) -> i32 {
-        let mut last_score = -1;
+    // The last winner's score, if anyone wins.
+    fn play_to_lose(&mut self) -> Option<i32> {
+        let mut last_score = None;
         for call in &self.sequence {
-            println!("Calling {}", call);
+            trace!(2, "Calling {}", call);
             for board in &mut self.boards {
                 if !board.complete() {
                     board.called(*call);
                     if board.complete() {
-                        println!("Bingo!!");
-                        println!("{:?}", board);
-                        last_score = board.score(*call);
+                        trace!(1, "Bingo!! {:?}", board);
+                        last_score = Some(board.score(*call));
                     }
                 }
             }
@@ -126,14 +98,66 @@ impl Game {
     }
 }
 
-pub fn step1() {
-    let mut game = Game::new("inputs/day04.txt");
+impl Solution for Game {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let mut boards = vec![];
+        let mut sequence = vec![];
+        let mut board: Vec<i32> = vec![];
+        for (idx, line) in input.lines().enumerate() {
+            if idx == 0 {
+                sequence = input.csv()?;
+            } else {
+                if line.is_empty() {
+                    continue;
+                }
+                for x in line.split_whitespace() {
+                    board.push(line.parse_field(x)?);
+                }
+                if board.len() == 25 {
+                    boards.push(BingoBoard::new(board.clone()));
+                    board.clear();
+                }
+            }
+        }
+        if !board.is_empty() {
+            return Err(input.error("incomplete bingo board at end of input"));
+        }
+        Ok(Game { boards, sequence })
+    }
+
+    fn part1(&self) -> Answer {
+        self.clone()
+            .play()
+            .ok_or_else(|| "no board wins".to_string())
+            .into()
+    }
 
-    println!("Winning score: {}", game.play());
+    fn part2(&self) -> Answer {
+        self.clone()
+            .play_to_lose()
+            .ok_or_else(|| "no board wins".to_string())
+            .into()
+    }
 }
 
-pub fn step2() {
-    let mut game = Game::new("inputs/day04.txt");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
+
+    #[test]
+    fn test_example() {
+        let game: Game = example(4);
+        assert_eq!(game.part1(), 4512.into());
+        assert_eq!(game.part2(), 1924.into());
+    }
 
-    println!("Losing score: {}", game.play_to_lose());
+    #[test]
+    fn test_no_winner() {
+        let mut game: Game = example(4);
+        game.sequence.truncate(4);
+        assert_eq!(game.clone().play(), None);
+        assert_eq!(game.clone().play_to_lose(), None);
+        assert_eq!(game.part1(), Answer::Unsolved("no board wins".to_string()));
+    }
 }
@@ -1,62 +1,53 @@
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{BufRead, BufReader},
-};
-
-pub fn read_list(filename: &str) -> Vec<String> {
-    let f = File::open(filename).expect("Could not read file");
-    BufReader::new(f).lines().map(|l| l.expect("Err")).collect()
-}
-
-#[derive(Debug, PartialEq, Clone, Copy, Hash, Eq)]
-struct Point {
-    pub x: i32,
-    pub y: i32,
-}
+use std::collections::HashMap;
 
-impl Point {
-    fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
-    }
-}
+use crate::{
+    geometry::{Point2, Vector2},
+    input::{Cursor, Input, InputError, ParseError},
+    solution::{Answer, Solution},
+};
 
 #[derive(Debug, Copy, Clone)]
 struct Line {
-    start: Point,
-    end: Point,
+    start: Point2,
+    end: Point2,
 
-    dx: i32,
-    dy: i32,
+    // one step along the line
+    direction: Vector2,
 }
 
 impl Line {
-    fn from_line(l: &str) -> Self {
-        let mut line_iter = l.split(" -> ");
-        let mut first = line_iter
-            .next()
-            .unwrap()
-            .split(',')
-            .map(|x| x.parse::<i32>().unwrap());
-        let mut second = line_iter
-            .next()
-            .unwrap()
-            .split(',')
-            .map(|x| x.parse::<i32>().unwrap());
-        let start = Point::new(first.next().unwrap(), first.next().unwrap());
-        let end = Point::new(second.next().unwrap(), second.next().unwrap());
-        let dx = end.x - start.x;
-        let dy = end.y - start.y;
-
-        Self { start, end, dx, dy }
+    /// Read `x1,y1 -> x2,y2`
+    fn read(c: &mut Cursor) -> Result<Self, ParseError> {
+        let start = Line::read_point(c)?;
+        c.expect(" -> ")?;
+        let at_end = c.clone();
+        let end = Line::read_point(c)?;
+        // stepping by `direction` has to land on `end`
+        let delta = end - start;
+        if delta.x() != 0 && delta.y() != 0 && delta.x().abs() != delta.y().abs() {
+            return Err(at_end.error("line must be horizontal, vertical or diagonal"));
+        }
+        let direction = delta.signum();
+
+        Ok(Self {
+            start,
+            end,
+            direction,
+        })
+    }
+
+    fn read_point(c: &mut Cursor) -> Result<Point2, ParseError> {
+        let x = c.number()?;
+        c.expect(",")?;
+        Ok(Point2::new(x, c.number()?))
     }
 
     fn is_vertical(&self) -> bool {
-        self.start.x == self.end.x
+        self.start.x() == self.end.x()
     }
 
     fn is_horizontal(&self) -> bool {
-        self.start.y == self.end.y
+        self.start.y() == self.end.y()
     }
 
     fn span(&self) -> LineSpan {
@@ -66,7 +57,7 @@ impl Line {
 
 struct LineSpan {
     line: Line,
-    cursor: Option<Point>,
+    cursor: Option<Point2>,
 }
 
 impl LineSpan {
@@ -79,15 +70,14 @@ impl LineSpan {
 }
 
 impl Iterator for LineSpan {
-    type Item = Point;
+    type Item = Point2;
 
     fn next(&mut self) -> Option<Self::Item> {
         let item = self.cursor;
         if self.cursor == Some(self.line.end) {
             self.cursor = None;
         } else if let Some(mut cursor) = item {
-            cursor.x += self.line.dx.signum();
-            cursor.y += self.line.dy.signum();
+            cursor += self.line.direction;
             self.cursor = Some(cursor);
         }
         item
@@ -95,28 +85,19 @@ impl Iterator for LineSpan {
 }
 
 #[derive(Debug)]
-struct Grid {
+pub struct Grid {
     lines: Vec<Line>,
 }
 
 impl Grid {
-    fn new(filename: &str, orthogonal: bool) -> Self {
-        let mut lines = vec![];
-        for line in read_list(filename) {
-            let candidate = Line::from_line(&line);
-            if !orthogonal || (candidate.is_horizontal() || candidate.is_vertical()) {
-                lines.push(candidate);
-            }
-        }
-
-        Self { lines }
-    }
-
-    fn count_danger_points(&self) -> i32 {
+    fn count_danger_points(&self, orthogonal: bool) -> i32 {
         let mut count = 0;
         let mut map = HashMap::new();
 
         for l in &self.lines {
+            if orthogonal && !(l.is_horizontal() || l.is_vertical()) {
+                continue;
+            }
             for p in l.span() {
                 if map.get(&p) == Some(&1) {
                     // this tracks the 1 -> >=2 transition
@@ -130,14 +111,54 @@ impl Grid {
     }
 }
 
-pub fn step1() {
-    let grid = Grid::new("inputs/day05.txt", true);
+impl Solution for Grid {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let mut lines = vec![];
+        for line in input.lines() {
+            lines.push(line.parse_with(Line::read)?);
+        }
+
+        Ok(Self { lines })
+    }
+
+    fn part1(&self) -> Answer {
+        // Orthogonal danger points
+        self.count_danger_points(true).into()
+    }
 
-    println!("Orthogonal danger points: {}", grid.count_danger_points());
+    fn part2(&self) -> Answer {
+        // All danger points
+        self.count_danger_points(false).into()
+    }
 }
 
-pub fn step2() {
-    let grid = Grid::new("inputs/day05.txt", false);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
 
-    println!("All danger points: {}", grid.count_danger_points());
+    #[test]
+    fn test_example() {
+        let grid: Grid = example(5);
+        assert_eq!(grid.part1(), 5.into());
+        assert_eq!(grid.part2(), 12.into());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |s: &str| Grid::parse(&Input::from_text(s)).unwrap_err().to_string();
+        assert_eq!(
+            err("0,9 -> 5,9\n8,0 -> 0;8"),
+            "<input>: line 2, col 9: expected `,`"
+        );
+        assert_eq!(err("0,9 => 5,9"), "<input>: line 1, col 4: expected ` -> `");
+        assert_eq!(
+            err("0,9 -> 5,9,"),
+            "<input>: line 1, col 11: unexpected `,`"
+        );
+        assert_eq!(
+            err("0,0 -> 1,3"),
+            "<input>: line 1, col 8: line must be horizontal, vertical or diagonal"
+        );
+    }
 }
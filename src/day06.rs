@@ -1,69 +1,135 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+use crate::{
+    input::{Input, InputError},
+    params::{Param, Params},
+    solution::{Answer, Solution},
 };
 
-pub fn read_csv_ints(filename: &str) -> Vec<usize> {
-    let f = File::open(filename).expect("Could not read file");
-    let mut line = String::new();
-    BufReader::new(f).read_line(&mut line).unwrap();
-    // parse() breaks on line ending, so need to trim that...
-    line.retain(|c| !c.is_whitespace());
-    line.split(',').map(|x| {x.parse::<usize>().unwrap()}).collect()
-}
+pub const PARAMS: &[Param] = &[
+    Param {
+        name: "days1",
+        default: 80,
+        min: 0,
+        max: 400,
+        help: "days to simulate in part 1",
+    },
+    Param {
+        name: "days2",
+        default: 256,
+        min: 0,
+        max: 400,
+        help: "days to simulate in part 2",
+    },
+];
 
-#[derive(Debug)]
-struct LanternSim {
+#[derive(Debug, Clone)]
+pub struct LanternSim {
     remaining: [usize; 9],
+
+    params: Params,
 }
 
 impl LanternSim {
-    fn new(filename: &str) -> Self {
-        let fish = read_csv_ints(filename);
-        let mut remaining = [0; 9];
-        for f in fish {
-            remaining[f] += 1;
-        }
-        Self {
-            remaining
-        }
-    }
-
-    fn step(&mut self) {
+    // None if there are too many fish to count
+    fn step(&mut self) -> Option<()> {
         let mut next_remaining = [0; 9];
         for idx in 0..9 {
             if idx == 0 {
                 // spawn fish
-                next_remaining[8] += self.remaining[idx];
-                next_remaining[6] += self.remaining[idx];
+                next_remaining[8] = self.remaining[idx];
+                next_remaining[6] = self.remaining[idx];
             } else {
-                next_remaining[idx - 1] += self.remaining[idx];
+                next_remaining[idx - 1] =
+                    next_remaining[idx - 1].checked_add(self.remaining[idx])?;
             }
         }
         self.remaining = next_remaining;
+        Some(())
+    }
+
+    fn total_fish(&self) -> Option<usize> {
+        self.remaining
+            .iter()
+            .try_fold(0usize, |total, &n| total.checked_add(n))
     }
 
-    fn total_fish(&self) -> usize {
-        self.remaining.iter().sum()
+    fn fish_after(&self, days: i64) -> Result<usize, String> {
+        let mut sim = self.clone();
+        (0..days)
+            .try_for_each(|_| sim.step())
+            .and_then(|()| sim.total_fish())
+            .ok_or_else(|| format!("too many fish to count after {} days", days))
     }
 }
 
-pub fn step1() {
-    let mut sim = LanternSim::new("inputs/day06.txt");
+impl Solution for LanternSim {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let line = input.first_line()?;
+        let mut remaining = [0; 9];
+        for field in line.trim().split(',') {
+            let f: usize = line.parse_field(field)?;
+            if f >= remaining.len() {
+                return Err(line.field_error("timer out of range", field.trim()));
+            }
+            remaining[f] += 1;
+        }
+        Ok(Self {
+            remaining,
+            params: Params::new(PARAMS),
+        })
+    }
 
-    for _ in 0..80 {
-        sim.step();
+    fn part1(&self) -> Answer {
+        self.fish_after(self.params.get("days1")).into()
     }
 
-    println!("Fish: {}", sim.total_fish());
+    fn part2(&self) -> Answer {
+        self.fish_after(self.params.get("days2")).into()
+    }
+
+    fn configure(&mut self, params: &Params) {
+        self.params = params.clone();
+    }
 }
 
-pub fn step2() {
-    let mut sim = LanternSim::new("inputs/day06.txt");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
 
-    for _ in 0..256 {
-        sim.step();
+    #[test]
+    fn test_example() {
+        let sim: LanternSim = example(6);
+        assert_eq!(sim.part1(), 5934.into());
+        assert_eq!(sim.part2(), 26984457539i64.into());
     }
 
-    println!("Fish: {}", sim.total_fish());
+    #[test]
+    fn test_parse_errors() {
+        let err = LanternSim::parse(&Input::from_text("3,4,12,1")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 1, col 5: timer out of range: `12`"
+        );
+    }
+
+    #[test]
+    fn test_days_param() {
+        let mut sim: LanternSim = example(6);
+        let mut params = Params::new(PARAMS);
+        params.set("days1", 18).unwrap();
+        sim.configure(&params);
+        assert_eq!(sim.part1(), 26.into());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let sim = LanternSim {
+            remaining: [usize::MAX / 4; 9],
+            params: Params::new(PARAMS),
+        };
+        assert_eq!(
+            sim.fish_after(2),
+            Err("too many fish to count after 2 days".to_string())
+        );
+    }
 }
@@ -1,28 +1,14 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+use crate::{
+    input::{Input, InputError},
+    solution::{Answer, Solution},
 };
 
-pub fn read_csv_ints(filename: &str) -> Vec<i32> {
-    let f = File::open(filename).expect("Could not read file");
-    let mut line = String::new();
-    BufReader::new(f).read_line(&mut line).unwrap();
-    // parse() breaks on line ending, so need to trim that...
-    line.retain(|c| !c.is_whitespace());
-    line.split(',').map(|x| x.parse::<i32>().unwrap()).collect()
-}
-
 #[derive(Debug)]
-struct CrabSumSwarm {
+pub struct CrabSumSwarm {
     crabsubs: Vec<i32>,
 }
 
 impl CrabSumSwarm {
-    fn new(filename: &str) -> Self {
-        let crabsubs = read_csv_ints(filename);
-        Self { crabsubs }
-    }
-
     fn cost(&self, pos: i32) -> i32 {
         let mut total = 0;
         for cs in &self.crabsubs {
@@ -43,14 +29,13 @@ impl CrabSumSwarm {
     fn min_cost_naive(&self, step2: bool) -> i32 {
         let mut min_cost = 1_000_000_000; // yes, I know...
 
-        for pos in *self.crabsubs.iter().min().unwrap()..*self.crabsubs.iter().max().unwrap() {
-            let pos_cost;
+        for pos in *self.crabsubs.iter().min().unwrap()..=*self.crabsubs.iter().max().unwrap() {
             // This is terrible, would be better to pass a cost function in.
-            if step2 {
-                pos_cost = self.cost_2(pos);
+            let pos_cost = if step2 {
+                self.cost_2(pos)
             } else {
-                pos_cost = self.cost(pos);
-            }
+                self.cost(pos)
+            };
             if pos_cost < min_cost {
                 min_cost = pos_cost;
             }
@@ -59,14 +44,41 @@ impl CrabSumSwarm {
     }
 }
 
-pub fn step1() {
-    let subs = CrabSumSwarm::new("inputs/day07.txt");
+impl Solution for CrabSumSwarm {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        Ok(Self {
+            crabsubs: input.csv()?,
+        })
+    }
 
-    println!("Min Cost: {}", subs.min_cost_naive(false));
+    fn part1(&self) -> Answer {
+        self.min_cost_naive(false).into()
+    }
+
+    fn part2(&self) -> Answer {
+        // new calc
+        self.min_cost_naive(true).into()
+    }
 }
 
-pub fn step2() {
-    let subs = CrabSumSwarm::new("inputs/day07.txt");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
+
+    #[test]
+    fn test_example() {
+        let crabs: CrabSumSwarm = example(7);
+        assert_eq!(crabs.part1(), 37.into());
+        assert_eq!(crabs.part2(), 168.into());
+    }
 
-    println!("Min Cost - new calc: {}", subs.min_cost_naive(true));
+    #[test]
+    fn test_one_position() {
+        for text in ["5", "3,3"] {
+            let crabs = CrabSumSwarm::parse(&Input::from_text(text)).unwrap();
+            assert_eq!(crabs.part1(), 0.into());
+            assert_eq!(crabs.part2(), 0.into());
+        }
+    }
 }
@@ -1,33 +1,29 @@
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{BufRead, BufReader},
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    input::{Input, InputError, Line},
+    solution::{Answer, Solution},
 };
 
-pub fn read_list(filename: &str) -> Vec<String> {
-    let f = File::open(filename).expect("Could not read file");
-    BufReader::new(f).lines().map(|l| l.expect("Err")).collect()
+/// One display's notes: the ten patterns it shows, and the four digits of
+/// its output, each as a set of segments (bit 0 for a, up to bit 6 for g).
+#[derive(Debug)]
+struct Entry {
+    controls: Vec<u8>,
+    outputs: Vec<u8>,
 }
 
-pub fn step1() {
-    let mut count = 0;
-    for entry in read_list("inputs/day08.txt") {
-        let mut entry_parts = entry.split(" | ");
-        let _controls = entry_parts.next().unwrap();
-        let outputs = entry_parts.next().unwrap();
-        for out in outputs.split(' ') {
-            match out.len() {
-                2 | 3 | 4 | 7 => count += 1,
-                _ => (),
-            }
-        }
-    }
-    println!("Count: {}", count);
+#[derive(Debug)]
+pub struct Notes {
+    entries: Vec<Entry>,
 }
 
-fn digitize(value: &str) -> u8 {
+fn digitize(line: &Line, value: &str) -> Result<u8, InputError> {
+    if value.is_empty() {
+        return Err(line.field_error("expected segment `a`-`g`", value));
+    }
     let mut result = 0;
-    for ch in value.chars() {
+    for (idx, ch) in value.char_indices() {
         match ch {
             'a' => result |= 1,
             'b' => result |= 2,
@@ -36,17 +32,28 @@ fn digitize(value: &str) -> u8 {
             'e' => result |= 16,
             'f' => result |= 32,
             'g' => result |= 64,
-            _ => unimplemented!("invalid char"),
+            _ => {
+                let segment = &value[idx..idx + ch.len_utf8()];
+                return Err(line.field_error("expected segment `a`-`g`", segment));
+            }
         }
     }
-    result
+    Ok(result)
 }
 
-fn decode_entry(entry: &str) -> usize {
-    let mut entry_parts = entry.split(" | ");
+// `count` space separated patterns from `field`, part of `line`
+fn read_patterns(line: &Line, field: &str, count: usize) -> Result<Vec<u8>, InputError> {
+    let patterns: Vec<&str> = field.split(' ').collect();
+    if patterns.len() != count {
+        return Err(line.field_error(&format!("expected {} patterns", count), field));
+    }
+    patterns.iter().map(|p| digitize(line, p)).collect()
+}
+
+// The output value, or None if no wiring of the segments fits.
+fn decode_entry(entry: &Entry) -> Option<usize> {
     let mut map = HashMap::new();
-    let controls = entry_parts.next().unwrap().split(' ').map(|s| digitize(s));
-    let outputs = entry_parts.next().unwrap().split(' ').map(|s| digitize(s));
+    let controls = entry.controls.iter().copied();
 
     let mut one_pattern = 0;
     let mut four_pattern = 0;
@@ -101,27 +108,103 @@ fn decode_entry(entry: &str) -> usize {
         }
     }
 
-    assert!(map.len() == 10);
+    // ten different patterns, each read as a different digit
+    if map.len() != 10 || map.values().collect::<HashSet<_>>().len() != 10 {
+        return None;
+    }
 
     let mut result = 0;
-    for digit in outputs {
-        result = 10 * result + map[&digit];
+    for digit in &entry.outputs {
+        result = 10 * result + map.get(digit)?;
+    }
+
+    Some(result)
+}
+
+impl Solution for Notes {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let mut entries = vec![];
+        for line in input.lines() {
+            let (controls, outputs) = line.split_pair(" | ")?;
+            entries.push(Entry {
+                controls: read_patterns(&line, controls, 10)?,
+                outputs: read_patterns(&line, outputs, 4)?,
+            });
+        }
+        Ok(Self { entries })
     }
 
-    result
+    fn part1(&self) -> Answer {
+        let mut count = 0;
+        for entry in &self.entries {
+            for out in &entry.outputs {
+                match out.count_ones() {
+                    2 | 3 | 4 | 7 => count += 1,
+                    _ => (),
+                }
+            }
+        }
+        count.into()
+    }
+
+    fn part2(&self) -> Answer {
+        let mut total = 0;
+
+        for (n, entry) in self.entries.iter().enumerate() {
+            match decode_entry(entry) {
+                Some(value) => total += value,
+                None => return Answer::Unsolved(format!("no wiring fits entry {}", n + 1)),
+            }
+        }
+        total.into()
+    }
 }
 
-pub fn step2() {
-    let mut total = 0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
 
-    assert!(
-        5353 == decode_entry(
-            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf"
-        )
-    );
+    #[test]
+    fn test_example() {
+        let notes: Notes = example(8);
+        assert_eq!(notes.part1(), 26.into());
+        assert_eq!(notes.part2(), 61229.into());
+    }
+
+    #[test]
+    fn test_decode_entry() {
+        let notes = Notes::parse(&Input::from_text(
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf\n\
+             ab ab cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb | cdfeb fcadb cdfeb cdbaf",
+        ))
+        .unwrap();
+        assert_eq!(decode_entry(&notes.entries[0]), Some(5353));
+        // 1 twice, and no 8
+        assert_eq!(decode_entry(&notes.entries[1]), None);
+        assert_eq!(
+            notes.part2(),
+            Answer::Unsolved("no wiring fits entry 2".to_string())
+        );
+    }
 
-    for entry in read_list("inputs/day08.txt") {
-        total += decode_entry(&entry);
+    #[test]
+    fn test_parse_errors() {
+        let err = Notes::parse(&Input::from_text("abc | def")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 1, col 1: expected 10 patterns: `abc`"
+        );
+        let err = Notes::parse(&Input::from_text("a b c d e f g ab ac ad | ab cd ef")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 1, col 26: expected 4 patterns: `ab cd ef`"
+        );
+        let err =
+            Notes::parse(&Input::from_text("a b c d e f g ab ac ad | ab cd ef gh")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 1, col 36: expected segment `a`-`g`: `h`"
+        );
     }
-    println!("Total: {}", total);
 }
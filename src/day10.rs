@@ -1,25 +1,14 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+use crate::{
+    input::{Input, InputError},
+    solution::{Answer, Solution},
 };
 
-pub fn read_list(filename: &str) -> Vec<String> {
-    let f = File::open(filename).expect("Could not read file");
-    BufReader::new(f).lines().map(|l| l.expect("Err")).collect()
-}
-
 #[derive(Debug)]
-struct NavSystem {
+pub struct NavSystem {
     lines: Vec<String>,
 }
 
 impl NavSystem {
-    fn new(filename: &str) -> Self {
-        Self {
-            lines: read_list(filename),
-        }
-    }
-
     // given line, return remaining stack and optional illegal character
     fn preprocess(&self, line: &str) -> (Vec<char>, Option<char>) {
         let mut stack = vec![];
@@ -28,29 +17,21 @@ impl NavSystem {
             match ch {
                 '(' | '[' | '{' | '<' => stack.push(ch),
                 // These following patterns are painful...
-                ')' => {
-                    if stack.pop() != Some('(') {
-                        illegal = Some(ch);
-                        break;
-                    }
+                ')' if stack.pop() != Some('(') => {
+                    illegal = Some(ch);
+                    break;
                 }
-                ']' => {
-                    if stack.pop() != Some('[') {
-                        illegal = Some(ch);
-                        break;
-                    }
+                ']' if stack.pop() != Some('[') => {
+                    illegal = Some(ch);
+                    break;
                 }
-                '}' => {
-                    if stack.pop() != Some('{') {
-                        illegal = Some(ch);
-                        break;
-                    }
+                '}' if stack.pop() != Some('{') => {
+                    illegal = Some(ch);
+                    break;
                 }
-                '>' => {
-                    if stack.pop() != Some('<') {
-                        illegal = Some(ch);
-                        break;
-                    }
+                '>' if stack.pop() != Some('<') => {
+                    illegal = Some(ch);
+                    break;
                 }
                 _ => (),
             }
@@ -67,7 +48,7 @@ impl NavSystem {
                 '}' => Some(1197),
                 '>' => Some(25137),
                 _ => None,
-            }
+            };
         }
         None
     }
@@ -107,8 +88,7 @@ impl NavSystem {
         let mut scores: Vec<i128> = self
             .lines
             .iter()
-            .map(|l| self.line_score(l))
-            .flatten()  // Rust TIL - remove Nones & unwrap Somes
+            .filter_map(|l| self.line_score(l)) // Rust TIL - remove Nones & unwrap Somes
             .collect();
 
         // return median value
@@ -117,14 +97,31 @@ impl NavSystem {
     }
 }
 
-pub fn step1() {
-    let nav = NavSystem::new("inputs/day10.txt");
+impl Solution for NavSystem {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        Ok(Self {
+            lines: input.lines().map(|l| l.to_string()).collect(),
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        self.syntax_error_score().into()
+    }
 
-    println!("{}", nav.syntax_error_score());
+    fn part2(&self) -> Answer {
+        self.autocomplete_score().into()
+    }
 }
 
-pub fn step2() {
-    let nav = NavSystem::new("inputs/day10.txt");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
 
-    println!("{}", nav.autocomplete_score());
+    #[test]
+    fn test_example() {
+        let nav: NavSystem = example(10);
+        assert_eq!(nav.part1(), 26397.into());
+        assert_eq!(nav.part2(), 288957.into());
+    }
 }
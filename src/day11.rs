@@ -1,140 +1,81 @@
-use std::{
-    collections::HashSet,
-    fs::File,
-    io::{BufRead, BufReader},
+use crate::{
+    automaton::{Automaton, Boundary, Neighbourhood, Rule},
+    grid::Grid,
+    input::{Input, InputError},
+    solution::{Answer, Solution},
 };
 
-pub fn read_list(filename: &str) -> Vec<String> {
-    let f = File::open(filename).expect("Could not read file");
-    BufReader::new(f).lines().map(|l| l.expect("Err")).collect()
-}
-
-#[derive(Debug)]
-struct OctoMap {
-    energy: [[u8; 10]; 10],
+/// Every octopus gains a unit of energy each step. Those above 9 flash,
+/// giving their neighbours (diagonals included) another unit, and finish
+/// the step with no energy.
+#[derive(Debug, Clone)]
+struct Flash;
 
-    flash_count: i32,
-}
+impl Rule for Flash {
+    type Cell = u8;
 
-impl OctoMap {
-    fn new(filename: &str) -> Self {
-        let mut energy: [[u8; 10]; 10] = Default::default();
-        for (idx, line) in read_list(filename).iter().enumerate() {
-            energy[idx] = line
-                .chars()
-                .map(|x| x.to_string().parse::<u8>().unwrap())
-                .collect::<Vec<u8>>()
-                .try_into()
-                .unwrap();
-        }
-        Self {
-            energy,
-            flash_count: 0,
-        }
+    fn neighbourhood(&self) -> Neighbourhood {
+        Neighbourhood::AllAround
     }
 
-    fn increment(&mut self) {
-        for row in 0..10 {
-            for col in 0..10 {
-                self.energy[row][col] += 1;
-            }
-        }
+    fn next(&self, energy: &u8, _neighbours: &[u8]) -> u8 {
+        energy + 1
     }
 
-    fn increment_neighbours(&mut self, row: usize, col: usize) {
-        if row >= 1 {
-            self.energy[row - 1][col] += 1;
-            if col >= 1 {
-                self.energy[row - 1][col - 1] += 1;
-            }
-            if col < 9 {
-                self.energy[row - 1][col + 1] += 1;
-            }
-        }
-        if row < 9 {
-            self.energy[row + 1][col] += 1;
-            if col >= 1 {
-                self.energy[row + 1][col - 1] += 1;
-            }
-            if col < 9 {
-                self.energy[row + 1][col + 1] += 1;
-            }
-        }
-        if col >= 1 {
-            self.energy[row][col - 1] += 1;
-        }
-        if col < 9 {
-            self.energy[row][col + 1] += 1;
-        }
+    fn fires(&self, energy: &u8) -> bool {
+        *energy > 9
     }
 
-    fn flashes(&mut self) -> bool {
-        let mut flashed = HashSet::new();
-        loop {
-            let mut flash_occurred = false;
-
-            for row in 0..10 {
-                for col in 0..10 {
-                    if flashed.contains(&(row, col)) {
-                        continue;
-                    }
-
-                    if self.energy[row][col] > 9 {
-                        flash_occurred = true;
-                        flashed.insert((row, col));
-                        self.increment_neighbours(row, col);
-                        self.flash_count += 1;
-                    }
-                }
-            }
-
-            if !flash_occurred {
-                break;
-            }
-        }
-
-        // was it a synchronized flash of all octopuses?
-        flashed.len() == 100
+    fn spread(&self, energy: &mut u8) {
+        *energy += 1;
     }
 
-    fn dissipate(&mut self) {
-        for row in 0..10 {
-            for col in 0..10 {
-                if self.energy[row][col] > 9 {
-                    self.energy[row][col] = 0;
-                }
-            }
+    fn settle(&self, energy: &mut u8, flashed: bool) {
+        if flashed {
+            *energy = 0;
         }
     }
+}
 
-    fn step(&mut self) -> bool {
-        self.increment();
-        let result = self.flashes();
-        self.dissipate();
+#[derive(Debug, Clone)]
+pub struct OctoMap {
+    octopi: Automaton<Flash>,
+}
 
-        result
+impl Solution for OctoMap {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let energy = Grid::digits(input.lines())?;
+        if energy.is_empty() {
+            return Err(input.error("no octopuses found"));
+        }
+        Ok(Self {
+            octopi: Automaton::new(Flash, energy, Boundary::Bounded),
+        })
     }
-}
 
-pub fn step1() {
-    let mut om = OctoMap::new("inputs/day11.txt");
+    fn part1(&self) -> Answer {
+        let mut om = self.octopi.clone();
+        om.run(100);
 
-    for _ in 0..100 {
-        om.step();
+        om.total_fired().into()
     }
 
-    println!("{}", om.flash_count);
+    fn part2(&self) -> Answer {
+        let mut om = self.octopi.clone();
+        // the first step where they all flash at once
+        om.run_until(|om| om.fired() == om.cells().len()).into()
+    }
 }
 
-pub fn step2() {
-    let mut om = OctoMap::new("inputs/day11.txt");
-    let mut counter = 1; // pesky off-by-one errors...
-    loop {
-        if om.step() {
-            break;
-        }
-        counter += 1;
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
 
-    println!("{}", counter);
+    #[test]
+    fn test_example() {
+        let octopi: OctoMap = example(11);
+        assert_eq!(octopi.part1(), 1656.into());
+        assert_eq!(octopi.part2(), 195.into());
+    }
 }
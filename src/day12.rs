@@ -1,147 +1,133 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fs::File,
-    io::{BufRead, BufReader},
+use std::collections::HashMap;
+
+use crate::{
+    input::{Input, InputError},
+    search,
+    solution::{Answer, Solution},
+    trace,
 };
 
-pub fn read_list(filename: &str) -> Vec<String> {
-    let f = File::open(filename).expect("Could not read file");
-    BufReader::new(f).lines().map(|l| l.expect("Err")).collect()
+#[derive(Debug)]
+pub struct CaveGraph {
+    names: Vec<String>,
+    // caves adjacent to each cave, by index into `names`
+    adj: Vec<Vec<usize>>,
+    start: usize,
+    end: usize,
 }
 
-#[derive(Debug)]
-struct CaveGraph {
-    // Using String here rather than &str to avoid caring about lifetimes.
-    adj_map: HashMap<String, HashSet<String>>,
+/// Where we are on a route through the caves, and what we can't revisit.
+#[derive(Debug, Clone, Copy)]
+struct Visit {
+    cave: usize,
+    // bit per small cave seen so far
+    seen: u64,
+    // has the one repeat visit to a small cave been used up?
+    revisited: bool,
 }
 
 impl CaveGraph {
-    fn new(filename: &str) -> Self {
-        let mut adj_map = HashMap::new();
-        for line in read_list(filename) {
-            // There must be a better way of 'splitting to a pair' (without
-            // resorting to regex...)
-            let mut line_parts = line.split('-');
-            let from = line_parts.next().unwrap();
-            let to = line_parts.next().unwrap();
-
-            // since this is not a directed graph, add both directions
-            let from_set = adj_map.entry(from.to_string()).or_insert_with(HashSet::new);
-            from_set.insert(to.to_string());
-
-            let to_set = adj_map.entry(to.to_string()).or_insert_with(HashSet::new);
-            to_set.insert(from.to_string());
-        }
-        Self { adj_map }
-    }
-
-    fn count_paths(&self) {
-        let mut visited = HashSet::new();
-
-        println!("{}", self.dfs("start".to_string(), &mut visited));
+    fn is_small(&self, cave: usize) -> bool {
+        self.names[cave].chars().all(|x| x.is_lowercase())
     }
 
-    fn dfs(&self, node: String, visited: &mut HashSet<String>) -> i32 {
-        let mut total = 0;
-        if node == "end" {
-            return 1;
-        }
-        if node.chars().all(|x| x.is_lowercase()) {
-            visited.insert(node.to_string());
-        }
-        for cave in self
-            .adj_map
-            .get(&node)
-            .expect("node missing from graph")
-            .iter()
-        {
-            if visited.contains(cave) {
-                continue;
+    fn next(&self, visit: &Visit) -> Vec<Visit> {
+        let mut result = vec![];
+        for &cave in &self.adj[visit.cave] {
+            let mut next = Visit { cave, ..*visit };
+            if self.is_small(cave) {
+                let bit = 1 << cave;
+                if visit.seen & bit == 0 {
+                    next.seen |= bit;
+                } else if !visit.revisited && cave != self.start {
+                    next.revisited = true;
+                } else {
+                    continue;
+                }
             }
-            total += self.dfs(cave.to_string(), &mut visited.clone());
+            result.push(next);
         }
-
-        total
+        result
     }
 
-    // Re-implemented for part 2 to accumulate paths in a set rather than
-    // just count them. Slower, but allows a simple / naive solution to
-    // just check for each possible value of 'cave allowed twice'.
-    fn count_paths_alt(&self) {
-        let mut visited = HashMap::new();
-        let mut paths = HashSet::new();
+    fn describe(&self, path: &[Visit]) -> String {
+        let names: Vec<&str> = path.iter().map(|v| self.names[v.cave].as_str()).collect();
+        names.join(",")
+    }
 
-        for allow_twice in self.adj_map.keys() {
-            if allow_twice.chars().all(|x| x.is_lowercase()) {
-                if allow_twice == "start" || allow_twice == "end" {
-                    continue;
-                }
-                self.dfs_alt(
-                    "start".to_string(),
-                    &mut visited,
-                    &mut paths,
-                    allow_twice.to_string(),
-                    "".to_string(),
-                );
-            }
-        }
-        println!("{}", paths.len());
+    /// Paths from start to end visiting small caves at most once, or if
+    /// `allow_revisit`, one of them twice.
+    fn count_paths(&self, allow_revisit: bool) -> usize {
+        let start = Visit {
+            cave: self.start,
+            seen: 1 << self.start,
+            revisited: !allow_revisit,
+        };
+        search::all_paths(
+            start,
+            |v| v.cave == self.end,
+            |v| self.next(v),
+            |path| {
+                trace!(1, "Path: {}", self.describe(path));
+            },
+        )
     }
+}
+
+impl Solution for CaveGraph {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let mut names = vec![];
+        let mut index = HashMap::new();
+        let mut adj: Vec<Vec<usize>> = vec![];
+        for line in input.lines() {
+            let (from, to) = line.split_pair("-")?;
+
+            let mut cave = |name: &str| {
+                *index.entry(name.to_string()).or_insert_with(|| {
+                    names.push(name.to_string());
+                    adj.push(vec![]);
+                    names.len() - 1
+                })
+            };
+            let (from, to) = (cave(from), cave(to));
 
-    fn dfs_alt(
-        &self,
-        node: String,
-        visited: &mut HashMap<String, i32>,
-        paths: &mut HashSet<String>,
-        allow_twice: String,
-        path: String,
-    ) {
-        if node == "end" {
-            //println!("Path: {}", &path);
-            paths.insert(path.clone());
+            // since this is not a directed graph, add both directions
+            adj[from].push(to);
+            adj[to].push(from);
         }
-        if node.chars().all(|x| x.is_lowercase()) {
-            let visit_count = visited.entry(node.clone()).or_insert(0);
-            *visit_count += 1;
-        }
-        let path = path + "," + &node;
-        for cave in self
-            .adj_map
-            .get(&node)
-            .expect("node missing from graph")
-            .iter()
-        {
-            if let Some(visit_count) = visited.get(&cave.to_string()) {
-                if cave == &allow_twice {
-                    // Rust quibble (again) - need for either *x or &1
-                    // There's probably a reason this can't be implicit for i32
-                    // (but there is implicit eq for various str/String things)
-                    if *visit_count > 1 {
-                        continue;
-                    }
-                } else if *visit_count > 0 {
-                    continue;
-                }
-            }
-            self.dfs_alt(
-                cave.to_string(),
-                &mut visited.clone(),
-                paths,
-                allow_twice.clone(),
-                path.clone(),
-            );
+        let (Some(&start), Some(&end)) = (index.get("start"), index.get("end")) else {
+            return Err(input.error("cave system needs both `start` and `end`"));
+        };
+        // visited small caves are tracked as bits of a u64
+        if names.len() > 64 {
+            return Err(input.error("too many caves"));
         }
+        Ok(Self {
+            names,
+            adj,
+            start,
+            end,
+        })
     }
-}
 
-pub fn step1() {
-    let cg = CaveGraph::new("inputs/day12.txt");
+    fn part1(&self) -> Answer {
+        self.count_paths(false).into()
+    }
 
-    cg.count_paths();
+    fn part2(&self) -> Answer {
+        self.count_paths(true).into()
+    }
 }
 
-pub fn step2() {
-    let cg = CaveGraph::new("inputs/day12.txt");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
 
-    cg.count_paths_alt();
+    #[test]
+    fn test_example() {
+        let caves: CaveGraph = example(12);
+        assert_eq!(caves.part1(), 10.into());
+        assert_eq!(caves.part2(), 36.into());
+    }
 }
@@ -1,49 +1,18 @@
-use std::{
-    collections::HashSet,
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::collections::HashSet;
 
-pub fn read_list(filename: &str) -> Vec<String> {
-    let f = File::open(filename).expect("Could not read file");
-    BufReader::new(f).lines().map(|l| l.expect("Err")).collect()
-}
+use crate::{
+    input::{Cursor, Input, InputError, ParseError},
+    solution::{Answer, Solution},
+};
 
-#[derive(Debug)]
-struct PaperDots {
+#[derive(Debug, Clone)]
+pub struct PaperDots {
     dots: HashSet<(i32, i32)>,
 
     instructions: Vec<(String, i32)>,
 }
 
 impl PaperDots {
-    fn new(filename: &str) -> Self {
-        let mut dots = HashSet::new();
-        let mut instructions = Vec::new();
-        // Rust TIL: I've been using iter() too much when I should be using
-        // into_iter()...
-        let mut lines = read_list(filename).into_iter();
-        // Rust TIL: iteration takes ownership of iterator, so can't just
-        // re-use after break. Using `by_ref()` solves that.
-        // https://stackoverflow.com/a/57172670
-        for line in lines.by_ref() {
-            if line.is_empty() {
-                break;
-            }
-            let mut coords = line.split(',');
-            let x = coords.next().unwrap().parse::<i32>().unwrap();
-            let y = coords.next().unwrap().parse::<i32>().unwrap();
-            dots.insert((x, y));
-        }
-        for line in lines {
-            let mut instr = line.split('=');
-            let folddir: String = instr.next().unwrap().to_string();
-            let value: i32 = instr.next().unwrap().parse().unwrap();
-            instructions.push((folddir, value));
-        }
-        Self { dots, instructions }
-    }
-
     fn fold_up(&mut self, value: i32) {
         let mut new_dots = HashSet::new();
         for point in self.dots.iter() {
@@ -82,38 +51,123 @@ impl PaperDots {
         }
     }
 
-    fn draw(&self) {
+    /// Read a dot, `x,y`
+    fn read_dot(c: &mut Cursor) -> Result<(i32, i32), ParseError> {
+        let x = c.number()?;
+        c.expect(",")?;
+        Ok((x, c.number()?))
+    }
+
+    /// Read `fold along y=7`
+    fn read_fold(c: &mut Cursor) -> Result<(String, i32), ParseError> {
+        c.expect("fold along ")?;
+        let axis = match c.peek() {
+            Some(axis @ ('x' | 'y')) => axis,
+            _ => return Err(c.error("expected `x` or `y`")),
+        };
+        c.next_char();
+        c.expect("=")?;
+        Ok((format!("fold along {}", axis), c.number()?))
+    }
+
+    fn draw(&self) -> String {
+        let mut result = String::new();
         let height = self.dots.iter().max_by_key(|x| x.1).unwrap().1;
         let width = self.dots.iter().max_by_key(|x| x.0).unwrap().0;
 
         for line in 0..height + 1 {
+            if line > 0 {
+                result.push('\n');
+            }
             for x in 0..width + 1 {
                 if self.dots.contains(&(x, line)) {
-                    print!("#");
+                    result.push('#');
                 } else {
-                    print!(" ");
+                    result.push(' ');
                 }
             }
-            println!();
         }
+        result
     }
 }
 
-pub fn step1() {
-    let mut pd = PaperDots::new("inputs/day13.txt");
+impl Solution for PaperDots {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let mut dots = HashSet::new();
+        let mut instructions = Vec::new();
+        let mut lines = input.lines();
+        // Rust TIL: iteration takes ownership of iterator, so can't just
+        // re-use after break. Using `by_ref()` solves that.
+        // https://stackoverflow.com/a/57172670
+        for line in lines.by_ref() {
+            if line.is_empty() {
+                break;
+            }
+            dots.insert(line.parse_with(PaperDots::read_dot)?);
+        }
+        for line in lines {
+            instructions.push(line.parse_with(PaperDots::read_fold)?);
+        }
+        if dots.is_empty() {
+            return Err(input.error("no dots found"));
+        }
+        if instructions.is_empty() {
+            return Err(input.error("no fold instructions found"));
+        }
+        Ok(Self { dots, instructions })
+    }
 
-    let x = pd.instructions.get(0).unwrap();
-    // Rust question - what's the 'deep-copy' equivalent for a Tuple?
-    // or should I have pulled the tuple out to a struct deriving Clone?
-    let x: (String, i32) = (x.0.clone(), x.1);
-    pd.fold_step(&x);
+    fn part1(&self) -> Answer {
+        let mut pd = self.clone();
+
+        let x = pd.instructions.first().unwrap();
+        // Rust question - what's the 'deep-copy' equivalent for a Tuple?
+        // or should I have pulled the tuple out to a struct deriving Clone?
+        let x: (String, i32) = (x.0.clone(), x.1);
+        pd.fold_step(&x);
+
+        pd.dots.len().into()
+    }
 
-    println!("{}", pd.dots.len());
+    fn part2(&self) -> Answer {
+        let mut pd = self.clone();
+
+        pd.fold_all();
+        pd.draw().into()
+    }
 }
 
-pub fn step2() {
-    let mut pd = PaperDots::new("inputs/day13.txt");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
 
-    pd.fold_all();
-    pd.draw();
+    #[test]
+    fn test_example() {
+        let paper: PaperDots = example(13);
+        assert_eq!(paper.part1(), 17.into());
+        assert_eq!(
+            paper.part2(),
+            "#####\n#   #\n#   #\n#   #\n#####".to_string().into()
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |s: &str| {
+            PaperDots::parse(&Input::from_text(s))
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(err("6,10\n0 14\n"), "<input>: line 2, col 2: expected `,`");
+        assert_eq!(
+            err("6,10\n\nfold along z=7"),
+            "<input>: line 3, col 12: expected `x` or `y`"
+        );
+        assert_eq!(
+            err("6,10\n\nfold along y 7"),
+            "<input>: line 3, col 13: expected `=`"
+        );
+        assert_eq!(err("6,10\n"), "<input>: no fold instructions found");
+    }
 }
@@ -1,40 +1,55 @@
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{BufRead, BufReader},
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    input::{Cursor, Input, InputError, ParseError},
+    params::{Param, Params},
+    solution::{Answer, Solution},
 };
 
-pub fn read_list(filename: &str) -> Vec<String> {
-    let f = File::open(filename).expect("Could not read file");
-    BufReader::new(f).lines().map(|l| l.expect("Err")).collect()
+pub const PARAMS: &[Param] = &[
+    Param {
+        name: "steps1",
+        default: 10,
+        min: 0,
+        max: 20,
+        help: "insertion steps in part 1",
+    },
+    Param {
+        name: "steps2",
+        default: 40,
+        min: 0,
+        max: 60,
+        help: "insertion steps in part 2",
+    },
+];
+
+// Elements are single capital letters.
+fn read_element(c: &mut Cursor) -> Result<char, ParseError> {
+    match c.peek() {
+        Some(ch) if ch.is_ascii_uppercase() => {
+            c.next_char();
+            Ok(ch)
+        }
+        _ => Err(c.error("expected element (a capital letter)")),
+    }
 }
 
-#[derive(Debug)]
-struct Polymer {
+/// Read a rule like `CH -> B`.
+fn read_rule(c: &mut Cursor) -> Result<(String, String), ParseError> {
+    let pair = String::from_iter([read_element(c)?, read_element(c)?]);
+    c.expect(" -> ")?;
+    Ok((pair, read_element(c)?.to_string()))
+}
+
+#[derive(Debug, Clone)]
+pub struct Polymer {
     template: String,
     rules: HashMap<String, String>,
+
+    params: Params,
 }
 
 impl Polymer {
-    fn new(filename: &str) -> Self {
-        let mut lines = read_list(filename).into_iter();
-
-        let template = lines.next().unwrap();
-        lines.next(); // skip blank line
-
-        let mut rules = HashMap::new();
-        for line in lines {
-            let mut rule = line.split(" -> ");
-            let before = rule.next().unwrap();
-            let after = rule.next().unwrap();
-
-            // Rust: seems there's a choice between noisy 'to_string()'
-            // everywhere or noisy lifetimes everywhere?
-            rules.insert(before.to_string(), after.to_string());
-        }
-        Self { template, rules }
-    }
-
     fn polymerize(&mut self) {
         let mut result = String::new();
 
@@ -44,7 +59,7 @@ impl Polymer {
         for ch in ch_iter {
             let pair = last_ch.to_string() + &ch.to_string();
             last_ch = ch;
-            result.push_str(self.rules.get(&pair).unwrap());
+            result.push_str(&self.rules[&pair]);
             result.push(ch);
         }
 
@@ -68,18 +83,6 @@ impl Polymer {
     }
 }
 
-pub fn step1() {
-    let mut polymer = Polymer::new("inputs/day14.txt");
-
-    for _ in 0..10 {
-        polymer.polymerize();
-    }
-    println!(
-        "{}",
-        polymer.most_common_count() - polymer.least_common_count()
-    );
-}
-
 #[derive(Debug)]
 struct EfficientPolymer {
     pair_count: HashMap<String, i64>,
@@ -88,14 +91,11 @@ struct EfficientPolymer {
 }
 
 impl EfficientPolymer {
-    fn new(filename: &str) -> Self {
-        let mut lines = read_list(filename).into_iter();
-
+    fn new(polymer: &Polymer) -> Self {
         let mut pair_count = HashMap::new();
         let mut element_count = HashMap::new();
 
-        let template = lines.next().unwrap();
-        let mut ch_iter = template.chars();
+        let mut ch_iter = polymer.template.chars();
         let mut last_ch = ch_iter.next().unwrap();
 
         // I missed this to begin with, resulting in an off-by-one error :(
@@ -108,42 +108,35 @@ impl EfficientPolymer {
             last_ch = ch;
         }
 
-        lines.next(); // skip blank line
-
-        let mut rules = HashMap::new();
-        for line in lines {
-            let mut rule = line.split(" -> ");
-            let before = rule.next().unwrap();
-            let after = rule.next().unwrap();
-
-            // Rust: seems there's a choice between noisy 'to_string()'
-            // everywhere or noisy lifetimes everywhere?
-            rules.insert(before.to_string(), after.to_string());
-        }
         Self {
             pair_count,
             element_count,
-            rules,
+            rules: polymer.rules.clone(),
         }
     }
 
-    fn polymerize(&mut self) {
-        let mut result = HashMap::new();
+    // None if the counts get too big
+    fn polymerize(&mut self) -> Option<()> {
+        let mut result: HashMap<String, i64> = HashMap::new();
 
-        for (pair, count) in self.pair_count.iter() {
-            let newchar = self.rules.get(pair).unwrap();
+        for (pair, &count) in self.pair_count.iter() {
+            let newchar = &self.rules[pair];
             let element = String::from(newchar);
             let first = pair.chars().next().unwrap().to_string() + &element;
             let second = element + &pair.chars().nth(1).unwrap().to_string();
-            *result.entry(first).or_insert(0) += count;
-            *result.entry(second).or_insert(0) += count;
-            *self
+            for new_pair in [first, second] {
+                let total = result.entry(new_pair).or_insert(0);
+                *total = total.checked_add(count)?;
+            }
+            let total = self
                 .element_count
                 .entry(newchar.chars().next().unwrap())
-                .or_insert(0) += count;
+                .or_insert(0);
+            *total = total.checked_add(count)?;
         }
 
         self.pair_count = result;
+        Some(())
     }
 
     fn most_common_count(&self) -> i64 {
@@ -155,14 +148,133 @@ impl EfficientPolymer {
     }
 }
 
-pub fn step2() {
-    let mut polymer = EfficientPolymer::new("inputs/day14.txt");
+impl Solution for Polymer {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let mut lines = input.lines();
+
+        let template_line = match lines.next() {
+            Some(line) if !line.is_empty() => line,
+            _ => return Err(input.error("missing polymer template")),
+        };
+        let template = template_line.parse_with(|c| {
+            let mut template = String::new();
+            while !c.at_end() {
+                template.push(read_element(c)?);
+            }
+            Ok(template)
+        })?;
+        lines.next(); // skip blank line
+
+        let mut rules = HashMap::new();
+        let mut rule_lines = HashMap::new();
+        for line in lines {
+            let (before, after) = line.parse_with(read_rule)?;
+
+            // Rust: seems there's a choice between noisy 'to_string()'
+            // everywhere or noisy lifetimes everywhere?
+            rule_lines.insert(before.clone(), line);
+            rules.insert(before, after);
+        }
+
+        // Every pair in the template, and every pair the rules go on to
+        // make from those, needs a rule of its own.
+        let mut todo: Vec<_> = (1..template.len())
+            .map(|i| (template[i - 1..=i].to_string(), template_line))
+            .collect();
+        let mut seen = HashSet::new();
+        while let Some((pair, made_by)) = todo.pop() {
+            if !seen.insert(pair.clone()) {
+                continue;
+            }
+            let after = match rules.get(&pair) {
+                Some(after) => after,
+                None => return Err(made_by.error(&format!("no rule for pair `{}`", pair))),
+            };
+            let line = rule_lines[&pair];
+            todo.push((pair[..1].to_string() + after, line));
+            todo.push((after.to_string() + &pair[1..], line));
+        }
+
+        Ok(Self {
+            template,
+            rules,
+            params: Params::new(PARAMS),
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        let mut polymer = self.clone();
+
+        for _ in 0..self.params.get("steps1") {
+            polymer.polymerize();
+        }
+        (polymer.most_common_count() - polymer.least_common_count()).into()
+    }
+
+    fn part2(&self) -> Answer {
+        let mut polymer = EfficientPolymer::new(self);
+
+        let steps = self.params.get("steps2");
+        for _ in 0..steps {
+            if polymer.polymerize().is_none() {
+                return Answer::Unsolved(format!(
+                    "polymer too long to count after {} steps",
+                    steps
+                ));
+            }
+        }
+        (polymer.most_common_count() - polymer.least_common_count()).into()
+    }
+
+    fn configure(&mut self, params: &Params) {
+        self.params = params.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
+
+    #[test]
+    fn test_example() {
+        let polymer: Polymer = example(14);
+        assert_eq!(polymer.part1(), 1588.into());
+        assert_eq!(polymer.part2(), 2188189693529i64.into());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Polymer::parse(&Input::from_text("NNCB\n\nNN -> C\n")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 1: no rule for pair `CB`: `NNCB`"
+        );
+        // or a pair which a rule makes
+        let err = Polymer::parse(&Input::from_text("NN\n\nNN -> C\nNC -> N\n")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 3: no rule for pair `CN`: `NN -> C`"
+        );
+        let err = Polymer::parse(&Input::from_text("NN\n\nNN => C\n")).unwrap_err();
+        assert_eq!(err.to_string(), "<input>: line 3, col 3: expected ` -> `");
+        let err = Polymer::parse(&Input::from_text("NN\n\nNN -> cc\n")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 3, col 7: expected element (a capital letter)"
+        );
+        let err = Polymer::parse(&Input::from_text("NN\n\nNN -> CC\n")).unwrap_err();
+        assert_eq!(err.to_string(), "<input>: line 3, col 8: unexpected `C`");
+    }
 
-    for _ in 0..40 {
-        polymer.polymerize();
+    #[test]
+    fn test_overflow() {
+        let polymer: Polymer = example(14);
+        let mut efficient = EfficientPolymer::new(&polymer);
+        efficient
+            .pair_count
+            .values_mut()
+            .for_each(|n| *n = i64::MAX);
+        assert_eq!(efficient.polymerize(), None);
     }
-    println!(
-        "{}",
-        polymer.most_common_count() - polymer.least_common_count()
-    );
 }
@@ -1,75 +1,46 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fs::File,
-    io::{BufRead, BufReader},
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    grid::{Grid, Pos},
+    input::{Input, InputError},
+    params::{Param, Params},
+    search,
+    solution::{Answer, Solution},
 };
 
-pub fn read_list(filename: &str) -> Vec<String> {
-    let f = File::open(filename).expect("Could not read file");
-    BufReader::new(f).lines().map(|l| l.expect("Err")).collect()
-}
+pub const PARAMS: &[Param] = &[Param {
+    name: "expand",
+    default: 5,
+    min: 1,
+    max: 20,
+    help: "how many tiles across the full map is in part 2",
+}];
 
-#[derive(Debug)]
-struct RiskMaze {
-    risk: Vec<Vec<i32>>,
+#[derive(Debug, Clone)]
+pub struct RiskMaze {
+    risk: Grid<i32>,
 
-    line_width: usize,
+    params: Params,
 }
 
 impl RiskMaze {
-    fn new(filename: &str) -> Self {
-        let mut risk = vec![];
-        let mut line_width = 0;
-        for line in read_list(filename) {
-            line_width = line.len(); // don't care about repeated setting
-            risk.push(
-                line.chars()
-                    .map(|x| x.to_string().parse::<i32>().unwrap())
-                    .collect(),
-            );
-        }
-        Self { risk, line_width }
-    }
-
-    fn expand(&mut self) {
-        let mut new_grid = vec![];
-        for row in 0..5 * self.risk.len() {
-            let mut new_row = vec![];
-            for col in 0..5 * self.line_width {
-                let section = col / self.line_width + row / self.risk.len();
-                let mut raw = self.risk[row % self.risk.len()][col % self.line_width];
-                for _ in 0..section {
-                    raw += 1;
-                    if raw > 9 {
-                        raw = 1;
-                    }
+    fn expand(&mut self, times: usize) {
+        let (width, height) = (self.risk.width(), self.risk.height());
+        self.risk = Grid::from_fn(times * width, times * height, |(row, col)| {
+            let section = col / width + row / height;
+            let mut raw = self.risk[(row % height, col % width)];
+            for _ in 0..section {
+                raw += 1;
+                if raw > 9 {
+                    raw = 1;
                 }
-                new_row.push(raw);
             }
-            new_grid.push(new_row);
-        }
-        self.risk = new_grid;
-        self.line_width *= 5;
+            raw
+        });
     }
 
-    fn neighbours(&self, pos: (usize, usize)) -> HashSet<(usize, usize)> {
-        let mut n = HashSet::new();
-        let row = pos.0;
-        let col = pos.1;
-        if row >= 1 {
-            n.insert((row - 1, col));
-        }
-        if row < self.risk.len() - 1 {
-            n.insert((row + 1, col));
-        }
-        if col >= 1 {
-            n.insert((row, col - 1));
-        }
-        if col < self.line_width - 1 {
-            n.insert((row, col + 1));
-        }
-
-        n
+    fn target(&self) -> Pos {
+        (self.risk.height() - 1, self.risk.width() - 1)
     }
 
     fn _broken_populate(&self) -> i32 {
@@ -79,30 +50,32 @@ impl RiskMaze {
         let mut boundary = HashSet::new();
         let mut already = HashSet::new();
         let start = (0, 0);
-        let target = (self.risk.len() - 1, self.line_width - 1);
+        let target = self.target();
         already.insert(start);
         let mut risk = HashMap::new();
         risk.insert(start, 0);
         boundary.insert((1, 0));
         boundary.insert((0, 1));
-        while risk.get(&target).is_none() {
+        while !risk.contains_key(&target) {
             // What cells are adjacent to our current boundary?
             let mut new_boundary = HashSet::new();
             for b in &boundary {
                 new_boundary.extend(
-                    self.neighbours(*b)
+                    self.risk
+                        .neighbours4(*b)
+                        .collect::<HashSet<Pos>>()
                         .difference(&already)
                         .copied()
-                        .collect::<HashSet<(usize, usize)>>(),
+                        .collect::<HashSet<Pos>>(),
                 );
             }
 
             // determine min risk for each item in new_boundary and add to risk
             for pos in boundary.clone() {
-                let pos_risk = self.risk[pos.0][pos.1];
+                let pos_risk = self.risk[pos];
 
                 let mut min_risk = 99999999;
-                for n in self.neighbours(pos) {
+                for n in self.risk.neighbours4(pos) {
                     if already.contains(&n) {
                         let candidate = risk.get(&n).unwrap();
                         if candidate < &min_risk {
@@ -126,54 +99,83 @@ impl RiskMaze {
         *risk.get(&target).unwrap()
     }
 
-    fn bellman_ford(&self) -> i32 {
-        // This is just easier than Dijkstra, and I don't care about
-        // runtime speed too much.
-        let start = (0, 0);
-        let target = (self.risk.len() - 1, self.line_width - 1);
-        let mut risk = HashMap::new();
+    fn lowest_risk(&self) -> i32 {
+        // A*, with the Manhattan distance as the heuristic: every step
+        // costs at least 1.
+        let target = self.target();
+        let (risk, _path) = search::astar(
+            (0, 0),
+            |&pos| pos == target,
+            |&pos| self.risk.neighbours4(pos).map(|n| (n, self.risk[n])),
+            |&(row, col)| ((target.0 - row) + (target.1 - col)) as i32,
+        )
+        .expect("target is always reachable");
+        risk
+    }
+}
 
-        for row in 0..self.risk.len() {
-            for col in 0..self.line_width {
-                risk.insert((row, col), 99999);
-            }
+impl Solution for RiskMaze {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let risk = Grid::digits(input.lines())?;
+        if risk.is_empty() {
+            return Err(input.error("empty risk map"));
         }
-        risk.insert(start, 0);
-
-        let mut keep_going;
-        loop {
-            keep_going = false;
-            for row in 0..self.risk.len() {
-                for col in 0..self.line_width {
-                    let a = (row, col);
-                    for b in self.neighbours((row, col)) {
-                        let w = self.risk[b.0][b.1];
-                        let min_dist = risk.get(&a).unwrap() + w;
-                        if min_dist < *risk.get(&b).unwrap() {
-                            risk.insert(b, min_dist);
-                            keep_going = true;
-                        }
-                    }
-                }
-            }
-            if !keep_going {
-                break;
+        // the A* estimate relies on every step costing at least 1
+        for line in input.lines() {
+            if let Some(idx) = line.find('0') {
+                return Err(line.field_error("risk levels are 1 to 9", &line[idx..=idx]));
             }
         }
+        Ok(Self {
+            risk: risk.map(|&x| x as i32),
+            params: Params::new(PARAMS),
+        })
+    }
 
-        *risk.get(&target).unwrap()
+    fn part1(&self) -> Answer {
+        // 602
+        self.lowest_risk().into()
     }
-}
 
-pub fn step1() {
-    let rm = RiskMaze::new("inputs/day15.txt");
-    // 602
-    println!("{}", rm.bellman_ford());
+    fn part2(&self) -> Answer {
+        let mut rm = self.clone();
+        rm.expand(self.params.get("expand") as usize);
+        // 2935
+        rm.lowest_risk().into()
+    }
+
+    fn configure(&mut self, params: &Params) {
+        self.params = params.clone();
+    }
 }
 
-pub fn step2() {
-    let mut rm = RiskMaze::new("inputs/day15.txt");
-    rm.expand();
-    // 2935
-    println!("{}", rm.bellman_ford());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
+
+    #[test]
+    fn test_example() {
+        let maze: RiskMaze = example(15);
+        assert_eq!(maze.part1(), 40.into());
+        assert_eq!(maze.part2(), 315.into());
+    }
+
+    #[test]
+    fn test_expand_param() {
+        let mut maze: RiskMaze = example(15);
+        let mut params = Params::new(PARAMS);
+        params.set("expand", 1).unwrap();
+        maze.configure(&params);
+        assert_eq!(maze.part2(), 40.into());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = RiskMaze::parse(&Input::from_text("116\n108\n213\n")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 2, col 2: risk levels are 1 to 9: `0`"
+        );
+    }
 }
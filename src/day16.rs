@@ -1,25 +1,24 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::vec;
 
-pub fn read_hex_chars(filename: &str) -> Vec<char> {
-    let f = File::open(filename).expect("Could not read file");
-    let mut line = String::new();
-    BufReader::new(f).read_line(&mut line).unwrap();
-    line.chars().collect()
-}
+use crate::{
+    input::{Input, InputError},
+    solution::{Answer, Solution},
+};
 
-struct BitIter {
-    input: Box<dyn Iterator<Item = char>>,
+// Rust: a concrete iterator type (rather than Box<dyn Iterator>) means
+// this can derive Clone, so a fresh reader can be taken for each part.
+/// The bits of a hex transmission, most significant first.
+#[derive(Clone)]
+pub struct BitIter {
+    input: vec::IntoIter<char>,
     current_char: Option<u32>,
     nibble_offset: i32,
 }
 
 impl BitIter {
-    fn new(input: Vec<char>) -> Self {
+    pub fn new(input: Vec<char>) -> Self {
         Self {
-            input: Box::new(input.into_iter()),
+            input: input.into_iter(),
             current_char: None,
             nibble_offset: 0,
         }
@@ -32,7 +31,7 @@ impl Iterator for BitIter {
     fn next(&mut self) -> Option<Self::Item> {
         let mut result: Self::Item = 0;
         if self.current_char.is_none() {
-            self.current_char = self.input.next().unwrap_or('0').to_digit(16)
+            self.current_char = self.input.next()?.to_digit(16)
         }
         if let Some(x) = self.current_char {
             result = (x as i32 >> (3 - self.nibble_offset)) & 1;
@@ -46,7 +45,8 @@ impl Iterator for BitIter {
     }
 }
 
-struct PacketReader {
+#[derive(Clone)]
+pub struct PacketReader {
     bits: BitIter,
     total_ver: i128,
     bit_pos: i32,
@@ -61,10 +61,11 @@ impl PacketReader {
         }
     }
 
-    fn read_packet(&mut self) -> i128 {
-        let pver = self.bits_value(3);
+    // Errors if the transmission ends before the packet does.
+    fn read_packet(&mut self) -> Result<i128, String> {
+        let pver = self.bits_value(3)?;
         self.total_ver += pver as i128;
-        let ptype = self.bits_value(3);
+        let ptype = self.bits_value(3)?;
         if ptype == 4 {
             self.read_literal()
         } else {
@@ -72,20 +73,20 @@ impl PacketReader {
         }
     }
 
-    fn read_literal(&mut self) -> i128 {
+    fn read_literal(&mut self) -> Result<i128, String> {
         let mut literal_value: i128 = 0;
         // read 5-bit blocks and accumulate in literal_value
         loop {
-            let x: i128 = self.bits_value(5).into();
+            let x: i128 = self.bits_value(5)?.into();
             literal_value = (literal_value << 4) | (x & 15);
             if x & 16 != 16 {
-                break literal_value;
+                break Ok(literal_value);
             }
         }
     }
 
-    fn read_operator(&mut self, ptype: i32) -> i128 {
-        let len_type = self.bits_value(1);
+    fn read_operator(&mut self, ptype: i32) -> Result<i128, String> {
+        let len_type = self.bits_value(1)?;
         let op_type = match ptype {
             0 => |x, y| x + y,
             1 => |x, y| x * y,
@@ -105,51 +106,126 @@ impl PacketReader {
         }
 
         if len_type == 0 {
-            let plen = self.bits_value(15);
+            let plen = self.bits_value(15)?;
             let end_pos = self.bit_pos + plen;
             // fold
             if ptype > 4 {
-                value = op_type(self.read_packet(), self.read_packet());
+                value = op_type(self.read_packet()?, self.read_packet()?);
             } else {
                 while self.bit_pos < end_pos {
-                    value = op_type(value, self.read_packet())
+                    value = op_type(value, self.read_packet()?)
                 }
             }
         } else {
-            let pcount = self.bits_value(11);
+            let pcount = self.bits_value(11)?;
             // fold
             if ptype > 4 {
-                value = op_type(self.read_packet(), self.read_packet());
+                value = op_type(self.read_packet()?, self.read_packet()?);
             } else {
                 for _ in 0..pcount {
-                    value = op_type(value, self.read_packet())
+                    value = op_type(value, self.read_packet()?)
                 }
             }
         }
 
-        value
+        Ok(value)
     }
 
-    fn bits_value(&mut self, x: i32) -> i32 {
+    fn bits_value(&mut self, x: i32) -> Result<i32, String> {
         self.bit_pos += x;
-        self.bits
-            .by_ref()
-            .take(x as usize)
-            .fold(0, |acc, v| acc << 1 | v)
+        (0..x)
+            .try_fold(0, |acc, _| Some(acc << 1 | self.bits.next()?))
+            .ok_or_else(|| "transmission ends part way through a packet".to_string())
     }
 }
 
-pub fn step1() {
-    let mut pr = PacketReader::new(read_hex_chars("inputs/day16.txt"));
+impl Solution for PacketReader {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let line = input.first_line()?;
+        let hex = line.trim();
+        if let Some((idx, bad)) = hex.char_indices().find(|(_, ch)| !ch.is_ascii_hexdigit()) {
+            let digit = &hex[idx..idx + bad.len_utf8()];
+            return Err(line.field_error("expected hex digit", digit));
+        }
+        if hex.is_empty() {
+            return Err(line.error("empty transmission"));
+        }
+        let reader = PacketReader::new(hex.chars().collect());
+        // check there's a whole packet there, so the parts can't fail
+        reader.clone().read_packet().map_err(|e| line.error(&e))?;
+        Ok(reader)
+    }
+
+    fn part1(&self) -> Answer {
+        let mut pr = self.clone();
+
+        pr.read_packet()
+            .map(|_| pr.total_ver)
+            // 967
+            .into()
+    }
 
-    let _ = pr.read_packet();
-    // 967
-    println!("Total version: {}", pr.total_ver);
+    fn part2(&self) -> Answer {
+        let mut pr = self.clone();
+
+        // 12883091136209
+        pr.read_packet().into()
+    }
 }
 
-pub fn step2() {
-    let mut pr = PacketReader::new(read_hex_chars("inputs/day16.txt"));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
 
-    // 12883091136209
-    println!("Final result: {}", pr.read_packet());
+    fn packet(hex: &str) -> PacketReader {
+        PacketReader::parse(&Input::from_text(hex)).unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        let reader: PacketReader = example(16);
+        assert_eq!(reader.part2(), 1.into());
+
+        // Version sums
+        for (hex, sum) in [
+            ("8A004A801A8002F478", 16),
+            ("620080001611562C8802118E34", 12),
+            ("C0015000016115A2E0802F182340", 23),
+            ("A0016C880162017C3686B18A3D4780", 31),
+        ] {
+            assert_eq!(packet(hex).part1(), sum.into(), "{}", hex);
+        }
+        // Expression values
+        for (hex, value) in [
+            ("C200B40A82", 3),
+            ("04005AC33890", 54),
+            ("880086C3E88112", 7),
+            ("CE00C43D881120", 9),
+            ("D8005AC2A8F0", 1),
+            ("F600BC2D8F", 0),
+            ("9C005AC2F8F0", 0),
+            ("9C0141080250320F1802104A08", 1),
+        ] {
+            assert_eq!(packet(hex).part2(), value.into(), "{}", hex);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = PacketReader::parse(&Input::from_text("  C2x0B4"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 1, col 5: expected hex digit: `x`"
+        );
+        let err = PacketReader::parse(&Input::from_text("\n")).err().unwrap();
+        assert_eq!(err.to_string(), "<input>: line 1: empty transmission");
+        let err = PacketReader::parse(&Input::from_text("C")).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 1: transmission ends part way through a packet: `C`"
+        );
+    }
 }
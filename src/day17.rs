@@ -1,94 +1,68 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+use crate::{
+    geometry::{Point2, Rect, Vector2},
+    input::{Cursor, Input, InputError, ParseError},
+    solution::{Answer, Solution},
 };
 
-pub fn read_list(filename: &str) -> Vec<String> {
-    let f = File::open(filename).expect("Could not read file");
-    BufReader::new(f).lines().map(|l| l.expect("Err")).collect()
-}
-
-#[derive(Debug, Default)]
-struct Probe {
-    xpos: i32,
-    ypos: i32,
+#[derive(Debug, Clone)]
+pub struct Probe {
+    pos: Point2,
+    vel: Vector2,
 
-    xvel: i32,
-    yvel: i32,
-
-    targetx: (i32, i32),
-    targety: (i32, i32),
-    max_height: i32,
+    target: Rect,
+    max_height: i64,
 }
 
 impl Probe {
-    fn new(filename: &str) -> Self {
-        let desc = &read_list(filename)[0];
-
-        let mut parts = desc.split(' ');
-        parts.next(); // 'target'
-        parts.next(); // 'area:'
-        let tx = parts.next().unwrap(); // xx..xx,
-        let ty = parts.next().unwrap(); // yy..yy
-        Self {
-            xpos: 0,
-            ypos: 0,
-            xvel: 0,
-            yvel: 0,
-            targetx: Probe::tsplit(tx),
-            targety: Probe::tsplit(ty),
+    /// Read `target area: x=20..30, y=-10..-5`
+    fn read_target(c: &mut Cursor) -> Result<Self, ParseError> {
+        c.expect("target area: ")?;
+        let targetx = Probe::tsplit(c, "x=")?;
+        c.expect(", ")?;
+        let targety = Probe::tsplit(c, "y=")?;
+        Ok(Self {
+            pos: Point2::ORIGIN,
+            vel: Vector2::ZERO,
+            target: Rect::from_spans([targetx, targety]),
             max_height: 0,
-        }
+        })
     }
 
-    fn tsplit(t: &str) -> (i32, i32) {
+    fn tsplit(c: &mut Cursor, axis: &str) -> Result<(i64, i64), ParseError> {
         // Yes, this really should be regex, but that's not in std...
-        let (_, mut range) = t.split_once('=').unwrap();
-        range = range.trim_end_matches(',');
-
-        let mut rp = range.split("..");
-        let a: i32 = rp.next().unwrap().parse().unwrap();
-        let b: i32 = rp.next().unwrap().parse().unwrap();
-        // we want smallest *absolute* value provided first
-        if a.abs() < b.abs() {
-            (a, b)
-        } else {
-            (b, a)
-        }
+        c.expect(axis)?;
+        let a: i64 = c.number().map_err(|e| e.context("range"))?;
+        c.expect("..").map_err(|e| e.context("range"))?;
+        let b: i64 = c.number().map_err(|e| e.context("range"))?;
+        Ok((a.min(b), a.max(b)))
     }
 
     fn inrange(&self) -> bool {
-        // targety values are negative, first is always abs-smaller.
-        self.xpos >= self.targetx.0
-            && self.xpos <= self.targetx.1
-            && self.ypos <= self.targety.0
-            && self.ypos >= self.targety.1
+        self.target.contains(&self.pos)
     }
 
     fn beyond(&self) -> bool {
-        self.ypos <= self.targety.1 || self.xpos >= self.targetx.1
+        // the target is below and to the right of the start
+        self.pos.y() <= self.target.min.y() || self.pos.x() >= self.target.max.x()
     }
 
     fn step(&mut self) {
-        self.xpos += self.xvel;
-        self.ypos += self.yvel;
-        self.xvel -= self.xvel.signum(); // drag
-        self.yvel -= 1; // gravity
+        self.pos += self.vel;
+        // drag and gravity
+        self.vel -= Vector2::new(self.vel.x().signum(), 1);
 
-        if self.ypos >= self.max_height {
-            self.max_height = self.ypos;
+        if self.pos.y() >= self.max_height {
+            self.max_height = self.pos.y();
         }
     }
 
-    fn reset(&mut self, xv: i32, yv: i32) {
-        self.xpos = 0;
-        self.ypos = 0;
-        self.xvel = xv;
-        self.yvel = yv;
+    fn reset(&mut self, xv: i64, yv: i64) {
+        self.pos = Point2::ORIGIN;
+        self.vel = Vector2::new(xv, yv);
         self.max_height = 0;
     }
 
-    fn iterate(&mut self, xv: i32, yv: i32) -> bool {
+    fn iterate(&mut self, xv: i64, yv: i64) -> bool {
         self.reset(xv, yv);
         loop {
             self.step();
@@ -101,11 +75,11 @@ impl Probe {
         }
     }
 
-    fn search(&mut self) -> i32 {
+    fn search(&mut self) -> i64 {
         let mut max_height = 0;
         // Cover search space - 1000 as max yvel is a hack.
-        for xv in 0..self.targetx.1 + 1 {
-            for yv in self.targety.1..1000 {
+        for xv in 0..=self.target.max.x() {
+            for yv in self.target.min.y()..1000 {
                 if self.iterate(xv, yv) && self.max_height > max_height {
                     max_height = self.max_height;
                 }
@@ -117,8 +91,8 @@ impl Probe {
     fn count_good(&mut self) -> i32 {
         let mut count = 0;
         // Cover search space - 1000 as max yvel is a hack.
-        for xv in 0..self.targetx.1 + 1 {
-            for yv in self.targety.1..1000 {
+        for xv in 0..=self.target.max.x() {
+            for yv in self.target.min.y()..1000 {
                 if self.iterate(xv, yv) {
                     count += 1;
                 }
@@ -128,16 +102,52 @@ impl Probe {
     }
 }
 
-pub fn step1() {
-    let mut probe = Probe::new("inputs/day17.txt");
+impl Solution for Probe {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        input.first_line()?.parse_with(Probe::read_target)
+    }
+
+    fn part1(&self) -> Answer {
+        let mut probe = self.clone();
 
-    // 5151
-    println!("Max height: {}", probe.search());
+        // 5151
+        probe.search().into()
+    }
+
+    fn part2(&self) -> Answer {
+        let mut probe = self.clone();
+
+        // 968
+        probe.count_good().into()
+    }
 }
 
-pub fn step2() {
-    let mut probe = Probe::new("inputs/day17.txt");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
 
-    // 968
-    println!("Count: {}", probe.count_good());
+    #[test]
+    fn test_example() {
+        let probe: Probe = example(17);
+        assert_eq!(probe.part1(), 45.into());
+        assert_eq!(probe.part2(), 112.into());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |s: &str| Probe::parse(&Input::from_text(s)).unwrap_err().to_string();
+        assert_eq!(
+            err("target area: x=20..30"),
+            "<input>: line 1, col 22: expected `, `"
+        );
+        assert_eq!(
+            err("target area: x=20..30, y=-10.-5"),
+            "<input>: line 1, col 29: expected `..` in range"
+        );
+        assert_eq!(
+            err("x=20..30, y=-10..-5"),
+            "<input>: line 1, col 1: expected `target area: `"
+        );
+    }
 }
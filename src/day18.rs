@@ -1,37 +1,30 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::{fmt, str::FromStr};
 
-pub fn read_list(filename: &str) -> Vec<String> {
-    let f = File::open(filename).expect("Could not read file");
-    BufReader::new(f).lines().map(|l| l.expect("Err")).collect()
-}
+use crate::{
+    input::{Cursor, Input, InputError, ParseError},
+    solution::{Answer, Solution},
+};
 
+/// A snailfish number; each element carries its nesting depth.
 #[derive(Debug, Clone, PartialEq)]
-enum Sfn {
+pub enum Sfn {
     Regular(i32, i32),
     Pair(Box<Sfn>, Box<Sfn>, i32),
 }
 
 impl Sfn {
-    fn from_str(s: &str) -> Sfn {
-        Sfn::read_value(&mut s.chars(), 0)
-    }
-
-    fn read_value(s: &mut dyn Iterator<Item = char>, depth: i32) -> Sfn {
-        let ch = s.next().unwrap();
-
-        if let Some(x) = ch.to_digit(10) {
-            Sfn::Regular(x as i32, depth)
-        } else {
+    fn read_value(c: &mut Cursor, depth: i32) -> Result<Sfn, ParseError> {
+        if c.eat("[") {
             // Start of a new pair
-            assert!(ch == '[');
-            let left = Sfn::read_value(s, depth + 1);
-            assert!(s.next() == Some(','));
-            let right = Sfn::read_value(s, depth + 1);
-            assert!(s.next() == Some(']'));
-            Sfn::pair(left, right, depth)
+            let left = Sfn::read_value(c, depth + 1)?;
+            c.expect(",")?;
+            let right = Sfn::read_value(c, depth + 1)?;
+            c.expect("]")?;
+            Ok(Sfn::pair(left, right, depth))
+        } else if c.peek().is_some_and(|ch| ch.is_ascii_digit()) {
+            Ok(Sfn::Regular(c.number()?, depth))
+        } else {
+            Err(c.error("expected number or `[`"))
         }
     }
 
@@ -179,7 +172,8 @@ impl Sfn {
         }
     }
 
-    fn add(&self, other: Sfn) -> Sfn {
+    /// Snailfish addition; the result still needs reducing.
+    pub fn add(&self, other: Sfn) -> Sfn {
         Sfn::Pair(Box::new(self.deepen()), Box::new(other.deepen()), 0)
     }
 
@@ -190,7 +184,7 @@ impl Sfn {
         }
     }
 
-    fn reduce(&self) -> Sfn {
+    pub fn reduce(&self) -> Sfn {
         let mut previous = self.clone();
         loop {
             let mut result = previous.explode();
@@ -224,13 +218,14 @@ impl Sfn {
         }
     }
 
-    fn magnitude(&self) -> i32 {
+    pub fn magnitude(&self) -> i32 {
         match self {
             Sfn::Regular(x, _) => *x,
             Sfn::Pair(a, b, _) => 3 * a.magnitude() + 2 * b.magnitude(),
         }
     }
 
+    #[cfg(test)]
     fn make_pair(a: i32, b: i32, d: i32) -> Sfn {
         Sfn::pair(Sfn::Regular(a, d + 1), Sfn::Regular(b, d + 1), d)
     }
@@ -238,58 +233,110 @@ impl Sfn {
     fn pair(a: Sfn, b: Sfn, d: i32) -> Sfn {
         Sfn::Pair(Box::new(a), Box::new(b), d)
     }
+}
 
-    // Rust question: clippy wants me to implement this as fmt::Display,
-    // but when I try that I get an infinite loop... What's going on?
-    fn to_string(&self) -> String {
+impl fmt::Display for Sfn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Sfn::Regular(x, _) => x.to_string(),
-            Sfn::Pair(x, y, _) => {
-                format!("[{},{}]", x.to_string(), y.to_string())
-            }
+            Sfn::Regular(x, _) => write!(f, "{}", x),
+            Sfn::Pair(x, y, _) => write!(f, "[{},{}]", x, y),
         }
     }
 }
 
-pub fn step1() {
-    let mut result: Option<Sfn> = None;
+impl FromStr for Sfn {
+    type Err = ParseError;
 
-    for line in read_list("inputs/day18.txt") {
-        let next_sfn = Sfn::from_str(&line);
-        if let Some(sfn) = result {
-            result = Some(sfn.add(next_sfn).reduce());
-        } else {
-            result = Some(next_sfn);
-        }
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        Cursor::parse_all(s, |c| Sfn::read_value(c, 0))
     }
+}
 
-    // 3359
-    println!("Magnitude: {}", result.unwrap().magnitude());
+#[derive(Debug)]
+pub struct Homework {
+    numbers: Vec<Sfn>,
 }
 
-pub fn step2() {
-    let mut max_mag = 0;
-    for line1 in read_list("inputs/day18.txt") {
-        for line2 in read_list("inputs/day18.txt") {
-            if line1 == line2 {
-                continue;
+impl Solution for Homework {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let numbers = input
+            .lines()
+            .map(|l| l.parse_with(|c| Sfn::read_value(c, 0)))
+            .collect::<Result<Vec<_>, _>>()?;
+        if numbers.is_empty() {
+            return Err(input.error("no snailfish numbers found"));
+        }
+        Ok(Self { numbers })
+    }
+
+    fn part1(&self) -> Answer {
+        let mut result: Option<Sfn> = None;
+
+        for next_sfn in &self.numbers {
+            if let Some(sfn) = result {
+                result = Some(sfn.add(next_sfn.clone()).reduce());
+            } else {
+                result = Some(next_sfn.clone());
             }
-            let mag = Sfn::from_str(&line1)
-                .add(Sfn::from_str(&line2))
-                .reduce()
-                .magnitude();
-            if mag > max_mag {
-                max_mag = mag;
+        }
+
+        // 3359
+        result.unwrap().magnitude().into()
+    }
+
+    fn part2(&self) -> Answer {
+        let mut max_mag = 0;
+        for sfn1 in &self.numbers {
+            for sfn2 in &self.numbers {
+                if sfn1 == sfn2 {
+                    continue;
+                }
+                let mag = sfn1.add(sfn2.clone()).reduce().magnitude();
+                if mag > max_mag {
+                    max_mag = mag;
+                }
             }
         }
+        // 4616
+        max_mag.into()
     }
-    // 4616
-    println!("Max Magnitude: {}", max_mag);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solution::example;
+
+    #[test]
+    fn test_example() {
+        let homework: Homework = example(18);
+        assert_eq!(homework.part1(), 4140.into());
+        assert_eq!(homework.part2(), 3993.into());
+    }
+
+    fn sfn(s: &str) -> Sfn {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |s: &str| {
+            Homework::parse(&Input::from_text(s))
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            err("[1,2]\n[[1,2],3"),
+            "<input>: line 2, col 9: expected `]`"
+        );
+        assert_eq!(err("[1;2]"), "<input>: line 1, col 3: expected `,`");
+        assert_eq!(
+            err("[1,x]"),
+            "<input>: line 1, col 4: expected number or `[`"
+        );
+        assert_eq!(err("[1,2]]"), "<input>: line 1, col 6: unexpected `]`");
+        assert_eq!(err(""), "<input>: no snailfish numbers found");
+    }
 
     #[test]
     fn test_regular_sfn() {
@@ -308,20 +355,20 @@ mod tests {
         let c = Sfn::Regular(5, 0);
         let d = Sfn::Regular(3, 0);
 
-        assert_eq!(a.add(b), Sfn::from_str("[1,1]"));
-        assert_eq!(c.add(d), Sfn::from_str("[5,3]"));
+        assert_eq!(a.add(b), sfn("[1,1]"));
+        assert_eq!(c.add(d), sfn("[5,3]"));
     }
 
     #[test]
     fn test_from_str() {
-        assert_eq!(Sfn::from_str("7"), Sfn::Regular(7, 0));
-        assert_eq!(Sfn::from_str("[1,2]"), Sfn::make_pair(1, 2, 0));
+        assert_eq!(sfn("7"), Sfn::Regular(7, 0));
+        assert_eq!(sfn("[1,2]"), Sfn::make_pair(1, 2, 0));
         assert_eq!(
-            Sfn::from_str("[[9,2],1]"),
+            sfn("[[9,2],1]"),
             Sfn::pair(Sfn::make_pair(9, 2, 1), Sfn::Regular(1, 1), 0)
         );
         assert_eq!(
-            Sfn::from_str("[[1,2],[[3,4],5]]"),
+            sfn("[[1,2],[[3,4],5]]"),
             Sfn::pair(
                 Sfn::make_pair(1, 2, 1),
                 Sfn::pair(Sfn::make_pair(3, 4, 2), Sfn::Regular(5, 2), 1),
@@ -332,74 +379,74 @@ mod tests {
 
     #[test]
     fn test_split() {
-        assert_eq!(Sfn::Regular(10, 0).split(), Sfn::from_str("[5,5]"));
-        assert_eq!(Sfn::Regular(11, 0).split(), Sfn::from_str("[5,6]"));
-        assert_eq!(Sfn::Regular(12, 0).split(), Sfn::from_str("[6,6]"));
-        assert_eq!(Sfn::make_pair(12, 3, 0).split(), Sfn::from_str("[[6,6],3]"));
-        assert_eq!(Sfn::make_pair(3, 11, 0).split(), Sfn::from_str("[3,[5,6]]"));
+        assert_eq!(Sfn::Regular(10, 0).split(), sfn("[5,5]"));
+        assert_eq!(Sfn::Regular(11, 0).split(), sfn("[5,6]"));
+        assert_eq!(Sfn::Regular(12, 0).split(), sfn("[6,6]"));
+        assert_eq!(Sfn::make_pair(12, 3, 0).split(), sfn("[[6,6],3]"));
+        assert_eq!(Sfn::make_pair(3, 11, 0).split(), sfn("[3,[5,6]]"));
         // Check it only applies to the first entry
         assert_eq!(
             Sfn::make_pair(10, 11, 0).split(),
-            Sfn::from_str("[5,5]").add(Sfn::Regular(11, 0))
+            sfn("[5,5]").add(Sfn::Regular(11, 0))
         );
     }
 
     #[test]
     fn test_explosive() {
-        assert_eq!(Sfn::from_str("[2,3]").explosive(), false);
-        assert_eq!(Sfn::from_str("[[2,3],1]").explosive(), false);
-        assert_eq!(Sfn::from_str("[2,[3,4]]").explosive(), false);
-        assert_eq!(Sfn::from_str("[[[[2,3],5],4],2]").explosive(), false);
-        assert_eq!(Sfn::from_str("[[[[1,[2,3]],2],5],3]").explosive(), true);
+        assert!(!sfn("[2,3]").explosive());
+        assert!(!sfn("[[2,3],1]").explosive());
+        assert!(!sfn("[2,[3,4]]").explosive());
+        assert!(!sfn("[[[[2,3],5],4],2]").explosive());
+        assert!(sfn("[[[[1,[2,3]],2],5],3]").explosive());
     }
 
     #[test]
     fn test_simple_pair() {
-        assert_eq!(Sfn::from_str("[5,2]").simple_pair(), true);
-        assert_eq!(Sfn::from_str("[[1,5],2]").simple_pair(), false);
+        assert!(sfn("[5,2]").simple_pair());
+        assert!(!sfn("[[1,5],2]").simple_pair());
     }
 
     #[test]
     fn test_explode_left() {
         assert_eq!(
-            Sfn::from_str("[[[[[9,8],1],2],3],4]").explode(),
-            Sfn::from_str("[[[[0,9],2],3],4]")
+            sfn("[[[[[9,8],1],2],3],4]").explode(),
+            sfn("[[[[0,9],2],3],4]")
         );
     }
 
     #[test]
     fn test_explode_right() {
         assert_eq!(
-            Sfn::from_str("[7,[6,[5,[4,[3,2]]]]]").explode(),
-            Sfn::from_str("[7,[6,[5,[7,0]]]]")
+            sfn("[7,[6,[5,[4,[3,2]]]]]").explode(),
+            sfn("[7,[6,[5,[7,0]]]]")
         );
     }
 
     #[test]
     fn test_explode() {
         assert_eq!(
-            Sfn::from_str("[[6,[5,[4,[3,2]]]],1]").explode(),
-            Sfn::from_str("[[6,[5,[7,0]]],3]")
+            sfn("[[6,[5,[4,[3,2]]]],1]").explode(),
+            sfn("[[6,[5,[7,0]]],3]")
         );
         assert_eq!(
-            Sfn::from_str("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]").explode(),
-            Sfn::from_str("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]")
+            sfn("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]").explode(),
+            sfn("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]")
         );
         assert_eq!(
-            Sfn::from_str("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]").explode(),
-            Sfn::from_str("[[3,[2,[8,0]]],[9,[5,[7,0]]]]")
+            sfn("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]").explode(),
+            sfn("[[3,[2,[8,0]]],[9,[5,[7,0]]]]")
         );
     }
 
     #[test]
     fn test_magnitude() {
-        assert_eq!(Sfn::from_str("[[1,2],[[3,4],5]]").magnitude(), 143);
+        assert_eq!(sfn("[[1,2],[[3,4],5]]").magnitude(), 143);
     }
 
     #[test]
     fn test_to_string() {
         assert_eq!(
-            Sfn::from_str("[[1,2],[[3,4],5]]").to_string(),
+            sfn("[[1,2],[[3,4],5]]").to_string(),
             "[[1,2],[[3,4],5]]".to_string()
         );
     }
@@ -407,10 +454,10 @@ mod tests {
     #[test]
     fn test_reduce() {
         assert_eq!(
-            Sfn::from_str("[[[[4,3],4],4],[7,[[8,4],9]]]")
-                .add(Sfn::from_str("[1,1]"))
+            sfn("[[[[4,3],4],4],[7,[[8,4],9]]]")
+                .add(sfn("[1,1]"))
                 .reduce(),
-            Sfn::from_str("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]")
+            sfn("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]")
         );
     }
 }
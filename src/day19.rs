@@ -1,99 +1,49 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fs::File,
-    io::{BufRead, BufReader},
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    geometry::{Point3, Rotation, Vector3},
+    input::{Cursor, Input, InputError, ParseError},
+    params::{Param, Params},
+    solution::{Answer, Solution},
+    trace,
 };
 
-pub fn read_list(filename: &str) -> Vec<String> {
-    let f = File::open(filename).expect("Could not read file");
-    BufReader::new(f).lines().map(|l| l.expect("Err")).collect()
-}
-
-#[derive(Debug, Hash, PartialEq, Eq, Clone)]
-struct Delta(i32, i32, i32);
-
-impl From<Vec<i32>> for Delta {
-    fn from(item: Vec<i32>) -> Self {
-        assert!(item.len() == 3);
-        Delta(item[0], item[1], item[2])
-    }
-}
-
-impl Delta {
-    fn translate(&self, other: &Self) -> Self {
-        Self(self.0 + other.0, self.1 + other.1, self.2 + other.2)
-    }
-
-    fn delta(&self, other: &Self) -> Self {
-        Self(other.0 - self.0, other.1 - self.1, other.2 - self.2)
-    }
-
-    fn rotate(&self, dir: i32) -> Self {
-        match dir {
-            0 => Delta(self.0, self.1, self.2),
-            1 => Delta(self.1, -self.0, self.2),
-            2 => Delta(-self.0, -self.1, self.2),
-            3 => Delta(-self.1, self.0, self.2),
-
-            4 => Delta(self.0, self.2, -self.1),
-            5 => Delta(self.2, -self.0, -self.1),
-            6 => Delta(-self.0, -self.2, -self.1),
-            7 => Delta(-self.2, self.0, -self.1),
-
-            8 => Delta(self.2, self.1, -self.0),
-            9 => Delta(self.1, -self.2, -self.0),
-            10 => Delta(-self.2, -self.1, -self.0),
-            11 => Delta(-self.1, self.2, -self.0),
-
-            12 => Delta(self.0, -self.1, -self.2),
-            13 => Delta(-self.1, -self.0, -self.2),
-            14 => Delta(-self.0, self.1, -self.2),
-            15 => Delta(self.1, self.0, -self.2),
-
-            16 => Delta(-self.2, self.1, self.0),
-            17 => Delta(self.1, self.2, self.0),
-            18 => Delta(self.2, -self.1, self.0),
-            19 => Delta(-self.1, -self.2, self.0),
-
-            20 => Delta(self.2, self.0, self.1),
-            21 => Delta(self.0, -self.2, self.1),
-            22 => Delta(-self.2, -self.0, self.1),
-            23 => Delta(-self.0, self.2, self.1),
-            _ => self.clone(),
-        }
-    }
-
-    fn mdist(&self, other: &Self) -> i32 {
-        (self.0 - other.0).abs() + (self.1 - other.1).abs() + (self.2 - other.2).abs()
-    }
+pub const PARAMS: &[Param] = &[Param {
+    name: "overlap",
+    default: 12,
+    min: 1,
+    max: 100,
+    help: "beacons two scanners must share to be matched up",
+}];
+
+/// Read a beacon position `x,y,z`
+fn read_beacon(c: &mut Cursor) -> Result<Point3, ParseError> {
+    let x = c.number()?;
+    c.expect(",")?;
+    let y = c.number()?;
+    c.expect(",")?;
+    Ok(Point3::new(x, y, c.number()?))
 }
 
 #[derive(Debug, Default, PartialEq, Clone)]
-struct Scanner {
-    beacons: HashSet<Delta>,
+pub struct Scanner {
+    beacons: HashSet<Point3>,
 
     ident: i32,
 }
 
 impl Scanner {
-    fn new(lines: Vec<String>, ident: i32) -> Self {
-        let mut beacons = HashSet::new();
-        for beacon in lines {
-            let d: Delta = beacon
-                .split(',')
-                .map(|x| x.parse::<i32>().unwrap())
-                .collect::<Vec<i32>>()
-                .into();
-
-            beacons.insert(d);
+    pub fn new(beacons: Vec<Point3>, ident: i32) -> Self {
+        Self {
+            beacons: beacons.into_iter().collect(),
+            ident,
         }
-        Self { beacons, ident }
     }
 
-    fn rotate(&self, dir: i32) -> Self {
+    pub fn rotate(&self, rotation: &Rotation) -> Self {
         let mut beacons = HashSet::new();
         for beacon in &self.beacons {
-            beacons.insert(beacon.rotate(dir));
+            beacons.insert(rotation.apply(*beacon));
         }
         Self {
             beacons,
@@ -101,10 +51,10 @@ impl Scanner {
         }
     }
 
-    fn translate(&self, delta: &Delta) -> Self {
+    pub fn translate(&self, offset: &Vector3) -> Self {
         let mut beacons = HashSet::new();
         for beacon in &self.beacons {
-            beacons.insert(beacon.translate(delta));
+            beacons.insert(*beacon + *offset);
         }
         Self {
             beacons,
@@ -112,31 +62,25 @@ impl Scanner {
         }
     }
 
-    fn count_matches(&self, other: &Scanner) -> i32 {
-        let mut count = 0;
-        for this_b in &self.beacons {
-            for that_b in &other.beacons {
-                if this_b == that_b {
-                    count += 1;
-                }
-            }
-        }
-        count
+    fn count_matches(&self, other: &Scanner) -> usize {
+        self.beacons.intersection(&other.beacons).count()
     }
 
-    fn overlaps(&self, other: &Scanner) -> Option<(Scanner, Delta)> {
-        // are there at least 12 beacons in `other` which could overlap?
-        for dir in 0..24 {
-            let rot_scanner = other.rotate(dir);
+    /// `other` moved into this scanner's frame, and its offset, if they
+    /// share at least `needed` beacons.
+    pub fn overlaps(&self, other: &Scanner, needed: usize) -> Option<(Scanner, Vector3)> {
+        // are there at least `needed` beacons in `other` which could overlap?
+        for rotation in Rotation::all() {
+            let rot_scanner = other.rotate(&rotation);
             for beacon in &self.beacons {
                 for other_beacon in &rot_scanner.beacons {
                     if beacon == other_beacon {
                         continue;
                     }
-                    let beacon_delta = other_beacon.delta(beacon);
+                    let beacon_delta = *beacon - *other_beacon;
                     let offset_scanner = rot_scanner.translate(&beacon_delta);
 
-                    if self.count_matches(&offset_scanner) >= 12 {
+                    if self.count_matches(&offset_scanner) >= needed {
                         return Some((offset_scanner, beacon_delta));
                     }
                 }
@@ -147,87 +91,143 @@ impl Scanner {
 }
 
 #[derive(Debug)]
-struct ScannerMap {
+pub struct ScannerMap {
     scanners: Vec<Scanner>,
+
+    params: Params,
 }
 
 impl ScannerMap {
-    fn new(filename: &str) -> Self {
-        let mut scanners: Vec<Scanner> = vec![];
-        let mut delta_lines: Vec<String> = vec![];
-        let mut s_id = 0;
-        for line in read_list(filename) {
-            if line.starts_with("---") {
-                delta_lines = vec![];
-            } else if line.is_empty() {
-                scanners.push(Scanner::new(delta_lines.clone(), s_id));
-                s_id += 1;
-            } else {
-                delta_lines.push(line);
-            }
-        }
-        // don't forget the last set of data
-        scanners.push(Scanner::new(delta_lines.clone(), s_id));
-
-        Self { scanners }
-    }
-
-    fn build_map(&self) -> (HashMap<i32, Scanner>, HashSet<Delta>) {
+    fn build_map(&self) -> Result<(HashMap<i32, Scanner>, HashSet<Vector3>), String> {
         let mut sm_fixed = HashMap::new();
         sm_fixed.insert(0, self.scanners[0].clone());
         let mut offsets = HashSet::new();
+        let needed = self.params.get("overlap") as usize;
 
         while sm_fixed.len() < self.scanners.len() {
+            // if a whole pass finds nothing new, neither will the next one
+            let before = sm_fixed.len();
             for o in sm_fixed.clone().values() {
                 for s in &self.scanners {
                     if sm_fixed.contains_key(&s.ident) {
                         continue;
                     }
-                    if let Some((fixed, offset)) = o.overlaps(s) {
-                        // println!("Got overlap {} with {} at offset {:?}",
-                        //          o.ident, s.ident, &offset);
+                    if let Some((fixed, offset)) = o.overlaps(s, needed) {
+                        trace!(
+                            1,
+                            "Got overlap {} with {} at offset {:?}",
+                            o.ident,
+                            s.ident,
+                            &offset
+                        );
                         sm_fixed.insert(s.ident, fixed);
                         offsets.insert(offset);
                     }
                 }
             }
+            if sm_fixed.len() == before {
+                return Err(format!(
+                    "scanners could not be aligned ({} of {} with {} beacons in common)",
+                    sm_fixed.len(),
+                    self.scanners.len(),
+                    needed
+                ));
+            }
         }
-        (sm_fixed, offsets)
+        Ok((sm_fixed, offsets))
     }
 }
 
-pub fn step1() {
-    let sm = ScannerMap::new("inputs/day19.txt");
+impl Solution for ScannerMap {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let mut scanners: Vec<Scanner> = vec![];
+        let mut delta_lines: Vec<Point3> = vec![];
+        let mut s_id = 0;
+        for line in input.lines() {
+            if line.starts_with("---") {
+                delta_lines = vec![];
+            } else if line.is_empty() {
+                scanners.push(Scanner::new(delta_lines.clone(), s_id));
+                s_id += 1;
+            } else {
+                delta_lines.push(line.parse_with(read_beacon)?);
+            }
+        }
+        // don't forget the last set of data
+        scanners.push(Scanner::new(delta_lines.clone(), s_id));
 
-    let (fixed, _) = sm.build_map();
+        Ok(Self {
+            scanners,
+            params: Params::new(PARAMS),
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        let (fixed, _) = match self.build_map() {
+            Ok(map) => map,
+            Err(e) => return Answer::Unsolved(e),
+        };
 
-    let mut beacons_fixed = HashSet::new();
-    for smf in fixed.values() {
-        for bf in &smf.beacons {
-            beacons_fixed.insert(bf);
+        let mut beacons_fixed = HashSet::new();
+        for smf in fixed.values() {
+            for bf in &smf.beacons {
+                beacons_fixed.insert(bf);
+            }
         }
+        beacons_fixed.len().into()
+    }
+
+    fn part2(&self) -> Answer {
+        // yes it is silly rebuilding this again for part2 since it's slow,
+        // but I'm going for consistency of the 'framework'... :)
+        let (_, offsets) = match self.build_map() {
+            Ok(map) => map,
+            Err(e) => return Answer::Unsolved(e),
+        };
+
+        let mut max_distance = 0;
+        for s1 in &offsets {
+            for s2 in &offsets {
+                if s1 == s2 {
+                    continue;
+                }
+                let distance = (*s1 - *s2).manhattan_len();
+                if distance > max_distance {
+                    max_distance = distance;
+                }
+            }
+        }
+        max_distance.into()
+    }
+
+    fn configure(&mut self, params: &Params) {
+        self.params = params.clone();
     }
-    println!("Beacon count: {}", beacons_fixed.len());
 }
 
-pub fn step2() {
-    // yes it is silly rebuilding this again for part2 since it's slow,
-    // but I'm going for consistency of the 'framework'... :)
-    let sm = ScannerMap::new("inputs/day19.txt");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
 
-    let (_, offsets) = sm.build_map();
+    #[test]
+    fn test_example() {
+        let scanners: ScannerMap = example(19);
+        assert_eq!(scanners.part1(), 79.into());
+        assert_eq!(scanners.part2(), 3621.into());
+    }
 
-    let mut max_distance = 0;
-    for s1 in &offsets {
-        for s2 in &offsets {
-            if s1 == s2 {
-                continue;
-            }
-            let distance = s1.mdist(s2);
-            if distance > max_distance {
-                max_distance = distance;
-            }
-        }
+    #[test]
+    fn test_unaligned() {
+        let mut scanners: ScannerMap = example(19);
+        let mut params = Params::new(PARAMS);
+        params.set("overlap", 50).unwrap();
+        scanners.configure(&params);
+        assert_eq!(
+            scanners.part1(),
+            Answer::Unsolved(
+                "scanners could not be aligned (1 of 5 with 50 beacons in common)".to_string()
+            )
+        );
     }
-    println!("Max distance: {}", max_distance);
 }
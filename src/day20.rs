@@ -1,151 +1,147 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+use crate::{
+    automaton::{Automaton, Boundary, Neighbourhood, Rule},
+    grid::Grid,
+    input::{Input, InputError},
+    params::{Param, Params},
+    solution::{Answer, Solution},
+    trace,
 };
 
-pub fn read_list(filename: &str) -> Vec<String> {
-    let f = File::open(filename).expect("Could not read file");
-    BufReader::new(f).lines().map(|l| l.expect("Err")).collect()
+pub const PARAMS: &[Param] = &[
+    Param {
+        name: "enhancements1",
+        default: 2,
+        min: 0,
+        max: 200,
+        help: "times to enhance the image in part 1",
+    },
+    Param {
+        name: "enhancements2",
+        default: 50,
+        min: 0,
+        max: 200,
+        help: "times to enhance the image in part 2",
+    },
+];
+
+/// Each pixel's 3x3 square, read as a 9-bit binary number, is the index
+/// of its new value in the enhancement algorithm.
+#[derive(Debug, Clone)]
+struct Enhance {
+    algorithm: Vec<bool>,
+}
+
+impl Rule for Enhance {
+    type Cell = bool;
+
+    fn neighbourhood(&self) -> Neighbourhood {
+        Neighbourhood::Square
+    }
+
+    fn next(&self, _pixel: &bool, square: &[bool]) -> bool {
+        let index = square.iter().fold(0, |v, &p| (v << 1) | p as usize);
+        self.algorithm[index]
+    }
 }
 
 #[derive(Debug, Clone)]
-struct Image {
-    pixels: Vec<Vec<u8>>,
-    algorithm: Vec<u8>,
-    background: u8,
-    line_width: usize,
+pub struct Image {
+    // The image is infinite, but all the pixels outside of our window on it
+    // are the same. Watch out for algorithm[0] being lit - then that
+    // "background" flashes on and off!
+    image: Automaton<Enhance>,
+
+    params: Params,
 }
 
 impl Image {
-    fn new(filename: &str) -> Self {
-        let mut pixels = vec![];
-        let mut algorithm = vec![];
-        let background = 0u8;
-        let mut line_width = 0;
-
-        for line in read_list(filename) {
-            if line.is_empty() {
-                continue;
-            }
-            if algorithm.is_empty() {
-                algorithm = line
-                    .chars()
-                    .map(|x| if x == '#' { 1u8 } else { 0u8 })
-                    .collect();
-                continue;
-            }
-
-            line_width = line.len(); // don't care about repeated setting
-            pixels.push(
-                line.chars()
-                    .map(|x| if x == '#' { 1u8 } else { 0u8 })
-                    .collect(),
-            );
+    // Only the window can be counted: if the background is lit too, so
+    // are infinitely many pixels.
+    fn count_lit(&self) -> Result<usize, String> {
+        if self.image.boundary() == &Boundary::Infinite(true) {
+            return Err("infinitely many pixels are lit".to_string());
         }
+        Ok(self.image.cells().cells().filter(|&&p| p).count())
+    }
 
-        Self {
-            pixels,
-            algorithm,
-            background,
-            line_width,
+    fn enhance(&mut self, times: usize) {
+        for _ in 0..times {
+            self.image.step();
+            trace!(
+                2,
+                "after {} enhancements:\n{}",
+                self.image.generation(),
+                self.image.cells().render(|&p| if p { '#' } else { '.' })
+            );
         }
     }
+}
 
-    fn enhance(&self) -> Self {
-        let mut pixels = vec![];
-        let algorithm = self.algorithm.clone();
-        let line_width = self.line_width + 2;
-
-        let mut background = self.background;
-
-        // evaluate new image including a 1 pixel border around current extent
-        for r in -1..=self.pixels.len() as i32 + 1 {
-            let mut new_row = vec![];
-            for c in -1..self.line_width as i32 + 1 {
-                let index = self.surround_value(r, c);
-                let algo_result = self.algorithm[index];
-                new_row.push(algo_result);
-            }
-            pixels.push(new_row);
+impl Solution for Image {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let mut lines = input.lines();
+        let line = lines
+            .next()
+            .ok_or_else(|| input.error("missing enhancement algorithm"))?;
+        let algorithm = line.pixels()?;
+        if algorithm.len() != 512 {
+            return Err(line.error("enhancement algorithm must be 512 characters"));
         }
 
-        // Check what happens at an arbitrary point a long way away from our
-        // image, represented by the `background` value.
-        if self.background == 0u8 && algorithm[0] == 1 {
-            // algorithm 0 is used when self & surrounding are all dark.
-            // the infinite plane is 'dark', but won't be next time
-            background = 1u8;
-        }
-        if self.background == 1u8 && self.algorithm[511] == 0 {
-            // algorithm 511 is used when self & surrounding are all lit
-            // the infinite plane is 'lit', but won't be next time
-            background = 0u8;
-        }
+        let pixels = Grid::pixels(lines.skip_while(|l| l.is_empty()))?;
 
-        Self {
-            pixels,
-            algorithm,
-            background,
-            line_width,
-        }
+        Ok(Self {
+            image: Automaton::new(Enhance { algorithm }, pixels, Boundary::Infinite(false)),
+            params: Params::new(PARAMS),
+        })
     }
 
-    fn surround_value(&self, row: i32, col: i32) -> usize {
-        let mut v: usize = 0;
-        for r in row - 1..=row + 1 {
-            // Rust question: not sure about this, I want to create the
-            // empty vec on-demand, but that's subject to a 'temporary
-            // freed while still in use' error. The clone fixes that, but
-            // feels very hacky, since most of the time it is unnecessary.
-            let line = self
-                .pixels
-                .get(r as usize)
-                .unwrap_or(&vec![self.background; self.line_width])
-                .clone();
-            for c in col - 1..=col + 1 {
-                let p = line.get(c as usize).unwrap_or(&self.background);
-                v = (v << 1) | *p as usize;
-            }
-        }
-        v
+    fn part1(&self) -> Answer {
+        let mut im = self.clone();
+        im.enhance(self.params.get("enhancements1") as usize);
+        im.count_lit().into()
     }
 
-    fn count_lit(&self) -> usize {
-        let mut count = 0;
-        for r in &self.pixels {
-            for p in r {
-                if *p == 1 {
-                    count += 1;
-                }
-            }
-        }
-        count
+    fn part2(&self) -> Answer {
+        let mut im = self.clone();
+        im.enhance(self.params.get("enhancements2") as usize);
+        im.count_lit().into()
     }
 
-    #[allow(dead_code)]
-    fn display(&self) {
-        for line in &self.pixels {
-            for p in line {
-                print!("{}", if *p == 1 { '#' } else { '.' });
-            }
-            println!();
-        }
+    fn configure(&mut self, params: &Params) {
+        self.params = params.clone();
     }
 }
 
-pub fn step1() {
-    let mut im = Image::new("inputs/day20.txt");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
 
-    im = im.enhance();
-    im = im.enhance();
-    println!("Lit pixels {}", im.count_lit());
-}
-
-pub fn step2() {
-    let mut im = Image::new("inputs/day20.txt");
+    #[test]
+    fn test_example() {
+        let image: Image = example(20);
+        assert_eq!(image.part1(), 35.into());
+        assert_eq!(image.part2(), 3351.into());
+    }
 
-    for _ in 0..50 {
-        im = im.enhance();
+    #[test]
+    fn test_flashing_background() {
+        // the example, but with a background which lights up on odd steps
+        // and goes dark again on even ones
+        let text = std::fs::read_to_string("inputs/examples/day20.txt").unwrap();
+        let (algorithm, rest) = text.split_once('\n').unwrap();
+        let algorithm = format!("#{}.", &algorithm[1..511]);
+        let mut image =
+            Image::parse(&Input::from_text(&format!("{}\n{}", algorithm, rest))).unwrap();
+        let mut params = Params::new(PARAMS);
+        params.set("enhancements1", 1).unwrap();
+        params.set("enhancements2", 2).unwrap();
+        image.configure(&params);
+        assert_eq!(
+            image.part1(),
+            Answer::Unsolved("infinitely many pixels are lit".to_string())
+        );
+        assert_eq!(image.part2(), 24.into());
     }
-    println!("Lit pixels {}", im.count_lit());
 }
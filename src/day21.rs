@@ -2,6 +2,30 @@
 
 use std::collections::HashMap;
 
+use crate::{
+    input::{Cursor, Input, InputError, ParseError},
+    params::{Param, Params},
+    solution::{Answer, Solution},
+    trace,
+};
+
+pub const PARAMS: &[Param] = &[
+    Param {
+        name: "target1",
+        default: 1000,
+        min: 1,
+        max: 100_000,
+        help: "score needed to win with the deterministic die",
+    },
+    Param {
+        name: "target2",
+        default: 21,
+        min: 1,
+        max: 30,
+        help: "score needed to win with the Dirac die",
+    },
+];
+
 #[derive(Default, Debug, Clone)]
 struct DetDie {
     state: i32,
@@ -38,32 +62,6 @@ impl Player {
     }
 }
 
-pub fn step1() {
-    // Rust: the by_ref() tripped me up for a while.
-    // I guess iterators which 'generate' rather than 'traverse' would
-    // normally want this, but rust only has the one Trait for both...
-    let mut dd: DetDie = Default::default();
-    let dd = dd.by_ref();
-
-    // Player 1 starting position: 8
-    // Player 2 starting position: 6
-    let mut p1 = Player::new(8);
-    let mut p2 = Player::new(6);
-
-    let losing_score = loop {
-        p1.advance(dd.take(3).sum());
-        if p1.score >= 1000 {
-            break p2.score;
-        }
-        p2.advance(dd.take(3).sum());
-        if p2.score >= 1000 {
-            break p1.score;
-        }
-    };
-
-    println!("Final result: {}", dd.roll_count * losing_score);
-}
-
 fn run_game(
     remain: i32,
     pos: i32,
@@ -96,47 +94,159 @@ fn run_game(
     ways
 }
 
-pub fn step2() {
-    // Player 1 starting position: 8
-    // Player 2 starting position: 6
-    let mut p1_throw_ways = HashMap::new();
-    let p1_complete = run_game(21, 8, 1, 0, &mut p1_throw_ways);
-    println!("p1: {}", p1_complete);
-    println!("{:?}", p1_throw_ways);
-
-    let mut p2_throw_ways = HashMap::new();
-    let p2_complete = run_game(21, 6, 1, 0, &mut p2_throw_ways);
-    println!("p2: {}", p2_complete);
-    println!("{:?}", p2_throw_ways);
-
-    let mut p1_win_count = 0;
-    let mut p2_win_count = 0;
-    let mut total_universes_p1 = 1;
-    let mut total_universes_p2 = 1;
-
-    // 10 rounds is sufficient for this input data
-    for round in 1..=10 {
-        // three dice rolls, each splitting universe three ways.
-        total_universes_p1 *= 27;
-        if let Some(p1_wins_this_throw) = p1_throw_ways.get(&round) {
-            // Some potential universes have terminated...
-            total_universes_p1 -= p1_wins_this_throw;
-            // Scale p1 wins by number of universes from previous (i.e. p2) throw
-            p1_win_count += p1_wins_this_throw * total_universes_p2;
+/// Read `Player N starting position: X`, returning N and X.
+fn read_start(c: &mut Cursor) -> Result<(usize, i32), ParseError> {
+    c.expect("Player ")?;
+    let player = c.number()?;
+    c.expect(" starting position: ")?;
+    let position = c.number()?;
+    if !(1..=10).contains(&position) {
+        return Err(c.error("position must be 1 to 10"));
+    }
+    Ok((player, position))
+}
+
+#[derive(Debug)]
+pub struct DiracDice {
+    p1_start: i32,
+    p2_start: i32,
+
+    params: Params,
+}
+
+impl Solution for DiracDice {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let mut starts = [None; 2];
+        for line in input.lines().filter(|line| !line.is_empty()) {
+            let (player, position) = line.parse_with(read_start)?;
+            match starts.get_mut(player.wrapping_sub(1)) {
+                Some(start @ None) => *start = Some(position),
+                Some(_) => return Err(line.error("player already has a position")),
+                None => return Err(line.error("expected player 1 or 2")),
+            }
         }
-        total_universes_p2 *= 27;
-        if let Some(p2_wins_this_throw) = p2_throw_ways.get(&round) {
-            // Some potential universes have terminated...
-            total_universes_p2 -= p2_wins_this_throw;
-            // Scale p2 wins by number of universes from previous (i.e. p1) throw
-            p2_win_count += p2_wins_this_throw * total_universes_p1;
+        let [Some(p1_start), Some(p2_start)] = starts else {
+            return Err(input.error("need starting positions for players 1 and 2"));
+        };
+        Ok(Self {
+            p1_start,
+            p2_start,
+            params: Params::new(PARAMS),
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        // Rust: the by_ref() tripped me up for a while.
+        // I guess iterators which 'generate' rather than 'traverse' would
+        // normally want this, but rust only has the one Trait for both...
+        let mut dd: DetDie = Default::default();
+        let dd = dd.by_ref();
+
+        let mut p1 = Player::new(self.p1_start);
+        let mut p2 = Player::new(self.p2_start);
+
+        let target = self.params.get("target1") as i32;
+        let losing_score = loop {
+            p1.advance(dd.take(3).sum());
+            if p1.score >= target {
+                break p2.score;
+            }
+            p2.advance(dd.take(3).sum());
+            if p2.score >= target {
+                break p1.score;
+            }
+        };
+
+        (dd.roll_count * losing_score).into()
+    }
+
+    fn part2(&self) -> Answer {
+        let target = self.params.get("target2") as i32;
+        let mut p1_throw_ways = HashMap::new();
+        let p1_complete = run_game(target, self.p1_start, 1, 0, &mut p1_throw_ways);
+        trace!(1, "p1: {}", p1_complete);
+        trace!(2, "{:?}", p1_throw_ways);
+
+        let mut p2_throw_ways = HashMap::new();
+        let p2_complete = run_game(target, self.p2_start, 1, 0, &mut p2_throw_ways);
+        trace!(1, "p2: {}", p2_complete);
+        trace!(2, "{:?}", p2_throw_ways);
+
+        let mut p1_win_count = 0;
+        let mut p2_win_count = 0;
+        let mut total_universes_p1 = 1;
+        let mut total_universes_p2 = 1;
+
+        // nobody is still playing after the longest game either could have
+        let rounds = p1_throw_ways
+            .keys()
+            .chain(p2_throw_ways.keys())
+            .copied()
+            .max()
+            .unwrap_or(0);
+        for round in 1..=rounds {
+            // three dice rolls, each splitting universe three ways.
+            total_universes_p1 *= 27;
+            if let Some(p1_wins_this_throw) = p1_throw_ways.get(&round) {
+                // Some potential universes have terminated...
+                total_universes_p1 -= p1_wins_this_throw;
+                // Scale p1 wins by number of universes from previous (i.e. p2) throw
+                p1_win_count += p1_wins_this_throw * total_universes_p2;
+            }
+            total_universes_p2 *= 27;
+            if let Some(p2_wins_this_throw) = p2_throw_ways.get(&round) {
+                // Some potential universes have terminated...
+                total_universes_p2 -= p2_wins_this_throw;
+                // Scale p2 wins by number of universes from previous (i.e. p1) throw
+                p2_win_count += p2_wins_this_throw * total_universes_p1;
+            }
+            trace!(
+                2,
+                "round {:2}: universes p1: {:8}, p2: {:8}",
+                round,
+                total_universes_p1,
+                total_universes_p2
+            );
         }
-        println!(
-            "round {:2}: universes p1: {:8}, p2: {:8}",
-            round, total_universes_p1, total_universes_p2
-        );
+
+        trace!(1, "p1 win universes: {}", p1_win_count);
+        trace!(1, "p2 win universes: {}", p2_win_count);
+        p1_win_count.max(p2_win_count).into()
+    }
+
+    fn configure(&mut self, params: &Params) {
+        self.params = params.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
+
+    #[test]
+    fn test_example() {
+        let game: DiracDice = example(21);
+        assert_eq!(game.part1(), 739785.into());
+        assert_eq!(game.part2(), 444356092776315i64.into());
     }
 
-    println!("p1 win universes: {}", p1_win_count);
-    println!("p2 win universes: {}", p2_win_count);
+    #[test]
+    fn test_parse_errors() {
+        let err = DiracDice::parse(&Input::from_text(
+            "Player 1 starting position: 4\nPlayer 1 starting position: 8",
+        ))
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 2: player already has a position: `Player 1 starting position: 8`"
+        );
+        let err =
+            DiracDice::parse(&Input::from_text("Player 2 starting position: 11")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 1, col 31: position must be 1 to 10"
+        );
+        assert!(DiracDice::parse(&Input::from_text("Player 1 starting position: 4")).is_err());
+    }
 }
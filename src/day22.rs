@@ -1,141 +1,73 @@
 use std::{
     cmp::{max, min},
     collections::HashSet,
-    fs::File,
-    io::{BufRead, BufReader},
+    fmt,
+    str::FromStr,
 };
 
-pub fn read_list(filename: &str) -> Vec<String> {
-    let f = File::open(filename).expect("Could not read file");
-    BufReader::new(f).lines().map(|l| l.expect("Err")).collect()
-}
+use crate::{
+    geometry::Cuboid,
+    input::{Cursor, Input, InputError, ParseError},
+    solution::{Answer, Solution},
+    trace,
+};
+
+// Up to three (left, center, right) spans resulting from a split
+type SplitSpans = (Option<(i64, i64)>, Option<(i64, i64)>, Option<(i64, i64)>);
 
+/// A cuboid, inclusive of its end points on each axis.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-struct Region {
-    x: (i64, i64),
-    y: (i64, i64),
-    z: (i64, i64),
+pub struct Region {
+    bounds: Cuboid,
 }
 
 impl Region {
-    fn from_str(s: &str) -> Self {
-        let mut extents = s.split(',');
-        let x = extents.next().unwrap().strip_prefix("x=").unwrap();
-        let y = extents.next().unwrap().strip_prefix("y=").unwrap();
-        let z = extents.next().unwrap().strip_prefix("z=").unwrap();
-
-        let pairify = |v: Vec<i64>| {
-            let mut vi = v.iter();
-            (*vi.next().unwrap(), *vi.next().unwrap())
-        };
+    pub fn new(x: (i64, i64), y: (i64, i64), z: (i64, i64)) -> Self {
         Region {
-            x: pairify(x.split("..").map(|v| v.parse::<i64>().unwrap()).collect()),
-            y: pairify(y.split("..").map(|v| v.parse::<i64>().unwrap()).collect()),
-            z: pairify(z.split("..").map(|v| v.parse::<i64>().unwrap()).collect()),
+            bounds: Cuboid::from_spans([x, y, z]),
         }
     }
 
-    fn overlaps(&self, other: Region) -> bool {
-        self.x.0 <= other.x.1
-            && self.x.1 >= other.x.0
-            && self.y.0 <= other.y.1
-            && self.y.1 >= other.y.0
-            && self.z.0 <= other.z.1
-            && self.z.1 >= other.z.0
-    }
-
-    fn split_x(
-        &self,
-        s: (Option<(i64, i64)>, Option<(i64, i64)>, Option<(i64, i64)>),
-    ) -> HashSet<Region> {
-        let mut result = HashSet::new();
-        //println!("split_x {:?} at {:?}", self, s);
-        // Check s is in range; also impossible to split a one-width region.
-        //assert!(s > self.x.0 && s <= self.x.1);
-        if let Some(x_1) = s.0 {
-            result.insert(Region {
-                x: x_1,
-                y: self.y,
-                z: self.z,
-            });
-        }
-        if let Some(x_2) = s.1 {
-            result.insert(Region {
-                x: x_2,
-                y: self.y,
-                z: self.z,
-            });
-        }
-        if let Some(x_3) = s.2 {
-            result.insert(Region {
-                x: x_3,
-                y: self.y,
-                z: self.z,
-            });
-        }
-        result
-    }
-    fn split_y(
-        &self,
-        s: (Option<(i64, i64)>, Option<(i64, i64)>, Option<(i64, i64)>),
-    ) -> HashSet<Region> {
-        let mut result = HashSet::new();
-        //println!("split_y {:?} at {:?}", self, s);
-        // Check s is in range; also impossible to split a one-width region.
-        //assert!(s > self.x.0 && s <= self.x.1);
-        if let Some(y_1) = s.0 {
-            result.insert(Region {
-                x: self.x,
-                y: y_1,
-                z: self.z,
-            });
-        }
-        if let Some(y_2) = s.1 {
-            result.insert(Region {
-                x: self.x,
-                y: y_2,
-                z: self.z,
-            });
-        }
-        if let Some(y_3) = s.2 {
-            result.insert(Region {
-                x: self.x,
-                y: y_3,
-                z: self.z,
-            });
-        }
-        result
-    }
-    fn split_z(
-        &self,
-        s: (Option<(i64, i64)>, Option<(i64, i64)>, Option<(i64, i64)>),
-    ) -> HashSet<Region> {
-        let mut result = HashSet::new();
-        // println!("split_z {:?} at {:?}", self, s);
-        // Check s is in range; also impossible to split a one-width region.
-        //assert!(s > self.x.0 && s <= self.x.1);
-        if let Some(z_1) = s.0 {
-            result.insert(Region {
-                x: self.x,
-                y: self.y,
-                z: z_1,
-            });
-        }
-        if let Some(z_2) = s.1 {
-            result.insert(Region {
-                x: self.x,
-                y: self.y,
-                z: z_2,
-            });
-        }
-        if let Some(z_3) = s.2 {
-            result.insert(Region {
-                x: self.x,
-                y: self.y,
-                z: z_3,
-            });
+    /// Read `x=10..12,y=10..12,z=10..12`
+    fn read(c: &mut Cursor) -> Result<Self, ParseError> {
+        let x = Region::read_span(c, "x=")?;
+        c.expect(",")?;
+        let y = Region::read_span(c, "y=")?;
+        c.expect(",")?;
+        let z = Region::read_span(c, "z=")?;
+        Ok(Region::new(x, y, z))
+    }
+
+    fn read_span(c: &mut Cursor, axis: &str) -> Result<(i64, i64), ParseError> {
+        c.expect(axis)?;
+        let start = c.clone();
+        let a = c.number().map_err(|e| e.context("range"))?;
+        c.expect("..").map_err(|e| e.context("range"))?;
+        let b = c.number().map_err(|e| e.context("range"))?;
+        if a > b {
+            return Err(start.error("range end is before its start"));
         }
-        result
+        Ok((a, b))
+    }
+
+    pub fn overlaps(&self, other: Region) -> bool {
+        self.bounds.intersects(&other.bounds)
+    }
+
+    fn span(&self, axis: usize) -> (i64, i64) {
+        self.bounds.span(axis)
+    }
+
+    /// This region cut into pieces along `axis`, one for each of the spans.
+    fn split_axis(&self, axis: usize, s: SplitSpans) -> HashSet<Region> {
+        trace!(3, "split {} on axis {} at {:?}", self, axis, s);
+        [s.0, s.1, s.2]
+            .into_iter()
+            .flatten()
+            .map(|span| Region {
+                bounds: self.bounds.with_span(axis, span),
+            })
+            .collect()
     }
 
     fn contained(span: (i64, i64), other: (i64, i64)) -> bool {
@@ -144,12 +76,9 @@ impl Region {
     fn disjoint(span: (i64, i64), other: (i64, i64)) -> bool {
         other.1 < span.0 || other.0 > span.1
     }
-    fn split_points(
-        span: (i64, i64),
-        other: (i64, i64),
-    ) -> (Option<(i64, i64)>, Option<(i64, i64)>, Option<(i64, i64)>) {
+    fn split_points(span: (i64, i64), other: (i64, i64)) -> SplitSpans {
         // The returned split point(s) are always *outside* the span.
-        //println!(" split_points {:?} {:?} ", span, other);
+        trace!(3, " split_points {:?} {:?}", span, other);
         assert!(span.0 <= span.1);
         assert!(other.0 <= other.1);
 
@@ -178,82 +107,71 @@ impl Region {
             max(left_center.0, right_center.0),
             min(left_center.1, right_center.1),
         ));
-        //println!(" -> ({:?}, {:?})", left, right);
+        trace!(3, " -> ({:?}, {:?})", left, right);
         (left, center, right)
     }
 
-    fn split_against(&self, other: Region) -> HashSet<Region> {
+    pub fn split_against(&self, other: Region) -> HashSet<Region> {
         let mut splitx = HashSet::new();
-        //println!("extension: {:?} / {:?}", self, other);
-        let splits_x = Region::split_points(self.x, other.x);
-        for rp in other.split_x(splits_x) {
+        trace!(2, "extension: {} / {}", self, other);
+        let splits_x = Region::split_points(self.span(0), other.span(0));
+        for rp in other.split_axis(0, splits_x) {
             splitx.insert(rp);
         }
-        //println!(" Following splitx: {:?}", splitx);
+        trace!(3, " Following splitx: {:?}", splitx);
         let mut splitxy = HashSet::new();
         for v in splitx {
             if self.overlaps(v) {
-                let splits_y = Region::split_points(self.y, v.y);
-                for rp in v.split_y(splits_y) {
+                let splits_y = Region::split_points(self.span(1), v.span(1));
+                for rp in v.split_axis(1, splits_y) {
                     splitxy.insert(rp);
                 }
             } else {
                 splitxy.insert(v);
             }
         }
-        //println!(" Following splitxy: {:?}", splitxy);
+        trace!(3, " Following splitxy: {:?}", splitxy);
         let mut splitxyz = HashSet::new();
         for v in splitxy {
             if self.overlaps(v) {
-                let splits_z = Region::split_points(self.z, v.z);
-                for rp in v.split_z(splits_z) {
+                let splits_z = Region::split_points(self.span(2), v.span(2));
+                for rp in v.split_axis(2, splits_z) {
                     splitxyz.insert(rp);
                 }
             } else {
                 splitxyz.insert(v);
             }
         }
-        //println!(" Following splitxyz: {:?}", splitxyz);
+        trace!(3, " Following splitxyz: {:?}", splitxyz);
         assert!(splitxyz.len() <= 7);
         splitxyz
     }
 
-    fn volume(&self) -> i64 {
-        // +1s below because regions are inclusive of endpoints
-        (self.x.1 - self.x.0 + 1) * (self.y.1 - self.y.0 + 1) * (self.z.1 - self.z.0 + 1)
+    pub fn volume(&self) -> i64 {
+        self.bounds.volume()
     }
 
-    fn is_contained_by(&self, other: Region) -> bool {
-        other.x.0 <= self.x.0
-            && other.x.1 >= self.x.1
-            && other.y.0 <= self.y.0
-            && other.y.1 >= self.y.1
-            && other.z.0 <= self.z.0
-            && other.z.1 >= self.z.1
+    pub fn is_contained_by(&self, other: Region) -> bool {
+        other.bounds.contains_box(&self.bounds)
     }
 
     fn is_init_region(&self) -> bool {
-        self.is_contained_by(Region {
-            x: (-50, 50),
-            y: (-50, 50),
-            z: (-50, 50),
-        })
+        self.is_contained_by(Region::new((-50, 50), (-50, 50), (-50, 50)))
     }
 }
 
-#[derive(Debug)]
-struct RegionSet {
+/// A union of regions, kept as a set of disjoint cuboids.
+#[derive(Debug, Clone, Default)]
+pub struct RegionSet {
     regions: HashSet<Region>,
 }
 
 impl RegionSet {
-    fn new() -> Self {
-        Self {
-            regions: HashSet::new(),
-        }
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    fn total_volume(&self) -> i64 {
+    pub fn total_volume(&self) -> i64 {
         self.check_disjoint();
         self.regions.iter().map(|r| r.volume()).sum()
     }
@@ -269,7 +187,7 @@ impl RegionSet {
         }
     }
 
-    fn add(&mut self, new_region: Region) {
+    pub fn add(&mut self, new_region: Region) {
         let mut to_add = HashSet::new();
         let mut to_remove = HashSet::new();
 
@@ -307,7 +225,7 @@ impl RegionSet {
         }
     }
 
-    fn subtract(&mut self, r: Region) {
+    pub fn subtract(&mut self, r: Region) {
         let mut to_add = HashSet::new();
         let mut to_remove = HashSet::new();
 
@@ -331,6 +249,25 @@ impl RegionSet {
     }
 }
 
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (x, y, z) = (self.span(0), self.span(1), self.span(2));
+        write!(
+            f,
+            "x={}..{},y={}..{},z={}..{}",
+            x.0, x.1, y.0, y.1, z.0, z.1
+        )
+    }
+}
+
+impl FromStr for Region {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        Cursor::parse_all(s, Region::read)
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 struct Instruction {
     r: Region,
@@ -338,44 +275,37 @@ struct Instruction {
 }
 
 impl Instruction {
-    fn from_str(s: &str) -> Self {
-        let mut words = s.split_whitespace();
-        let on = match words.next() {
-            Some("on") => true,
-            Some("off") => false,
-            _ => panic!("Invalid region state"),
+    /// Read `on x=10..12,y=10..12,z=10..12` (or `off ...`)
+    fn read(c: &mut Cursor) -> Result<Self, ParseError> {
+        let on = match c.clone().word() {
+            "on" => true,
+            "off" => false,
+            _ => return Err(c.error("expected `on` or `off`")),
         };
-        let r = Region::from_str(words.next().unwrap());
-        Instruction { r, on }
+        c.word();
+        c.skip_whitespace();
+        let r = Region::read(c)?;
+        Ok(Instruction { r, on })
     }
 }
 
-#[derive(Debug)]
-struct Reactor {
+#[derive(Debug, Clone)]
+pub struct Reactor {
     instructions: Vec<Instruction>,
 
     regions: RegionSet,
 }
 
 impl Reactor {
-    fn new(filename: &str) -> Self {
-        let mut instructions = vec![];
-        for line in read_list(filename) {
-            let instr = Instruction::from_str(&line);
-            instructions.push(instr);
-        }
-
-        let regions = RegionSet::new();
-
-        Self {
-            instructions,
-            regions,
-        }
-    }
-
     fn evaluate(&mut self, all_instructions: bool) {
-        for (_x, instr) in self.instructions.iter().enumerate() {
-            //println!("  ** Instruction {}: {:?}", _x, instr);
+        for (i, instr) in self.instructions.iter().enumerate() {
+            trace!(
+                1,
+                "  ** Instruction {}: {} {}",
+                i,
+                if instr.on { "on" } else { "off" },
+                instr.r
+            );
             if all_instructions || instr.r.is_init_region() {
                 if instr.on {
                     self.regions.add(instr.r);
@@ -387,58 +317,120 @@ impl Reactor {
     }
 }
 
-pub fn step1() {
-    let mut reactor = Reactor::new("inputs/day22.txt");
+impl Solution for Reactor {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let mut instructions = vec![];
+        for line in input.lines() {
+            instructions.push(line.parse_with(Instruction::read)?);
+        }
 
-    reactor.evaluate(false);
-    // 561032
-    println!(
-        "total_volume (init only) {}",
-        reactor.regions.total_volume()
-    );
-}
+        let regions = RegionSet::new();
+
+        Ok(Self {
+            instructions,
+            regions,
+        })
+    }
 
-pub fn step2() {
-    let mut reactor = Reactor::new("inputs/day22.txt");
+    fn part1(&self) -> Answer {
+        let mut reactor = self.clone();
 
-    reactor.evaluate(true);
-    // 1322825263376414
-    println!("total_volume {}", reactor.regions.total_volume());
+        reactor.evaluate(false);
+        // 561032
+        reactor.regions.total_volume().into()
+    }
+
+    fn part2(&self) -> Answer {
+        let mut reactor = self.clone();
+
+        reactor.evaluate(true);
+        // 1322825263376414
+        reactor.regions.total_volume().into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solution::example;
+
+    #[test]
+    fn test_example() {
+        let reactor: Reactor = example(22);
+        assert_eq!(reactor.part1(), 590784.into());
+
+        let small = "on x=10..12,y=10..12,z=10..12\n\
+                     on x=11..13,y=11..13,z=11..13\n\
+                     off x=9..11,y=9..11,z=9..11\n\
+                     on x=10..10,y=10..10,z=10..10";
+        let reactor = Reactor::parse(&Input::from_text(small)).unwrap();
+        assert_eq!(reactor.part1(), 39.into());
+        assert_eq!(reactor.part2(), 39.into());
+    }
+
+    fn region(s: &str) -> Region {
+        s.parse().unwrap()
+    }
 
     #[test]
     fn test_from_str() {
-        let i = Instruction::from_str("on x=1..10,y=11..20,z=-21..30");
-        assert_eq!(i.r.x, (1, 10));
-        assert_eq!(i.r.y, (11, 20));
-        assert_eq!(i.r.z, (-21, 30));
-        assert_eq!(i.on, true);
+        let i = Cursor::parse_all("on x=1..10,y=11..20,z=-21..30", Instruction::read).unwrap();
+        assert_eq!(i.r.span(0), (1, 10));
+        assert_eq!(i.r.span(1), (11, 20));
+        assert_eq!(i.r.span(2), (-21, 30));
+        assert!(i.on);
+    }
+
+    #[test]
+    fn test_display() {
+        let text = "x=-20..26,y=-36..17,z=-47..7";
+        assert_eq!(region(text).to_string(), text);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |s: &str| {
+            Reactor::parse(&Input::from_text(s))
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            err("on x=1..2,y=1..2,z=1..2\non x=1..2,y=3.4,z=1..2"),
+            "<input>: line 2, col 14: expected `..` in range"
+        );
+        assert_eq!(
+            err("toggle x=1..2,y=1..2,z=1..2"),
+            "<input>: line 1, col 1: expected `on` or `off`"
+        );
+        assert_eq!(
+            err("off x=1..2,z=1..2"),
+            "<input>: line 1, col 12: expected `y=`"
+        );
+        assert_eq!(
+            err("on x=5..2,y=1..2,z=1..2"),
+            "<input>: line 1, col 6: range end is before its start"
+        );
+        assert_eq!(
+            "x=1..2,y=1..2,z=1.."
+                .parse::<Region>()
+                .unwrap_err()
+                .to_string(),
+            "col 20: expected number in range"
+        );
     }
 
     #[test]
     fn test_region_volume() {
-        assert_eq!(Region::from_str("x=0..0,y=9..9,z=3..3").volume(), 1);
-        assert_eq!(Region::from_str("x=1..10,y=9..9,z=3..3").volume(), 10);
-        assert_eq!(Region::from_str("x=1..10,y=9..10,z=3..3").volume(), 20);
-        assert_eq!(Region::from_str("x=1..10,y=9..10,z=3..5").volume(), 60);
+        assert_eq!(region("x=0..0,y=9..9,z=3..3").volume(), 1);
+        assert_eq!(region("x=1..10,y=9..9,z=3..3").volume(), 10);
+        assert_eq!(region("x=1..10,y=9..10,z=3..3").volume(), 20);
+        assert_eq!(region("x=1..10,y=9..10,z=3..5").volume(), 60);
     }
 
     #[test]
     fn test_overlaps() {
-        let unit = Region {
-            x: (1, 1),
-            y: (1, 1),
-            z: (1, 1),
-        };
-        let pair = Region {
-            x: (1, 2),
-            y: (1, 1),
-            z: (1, 1),
-        };
+        let unit = Region::new((1, 1), (1, 1), (1, 1));
+        let pair = Region::new((1, 2), (1, 1), (1, 1));
 
         assert!(unit.overlaps(unit));
         assert!(unit.is_contained_by(unit));
@@ -452,72 +444,44 @@ mod tests {
     #[test]
     fn test_add_disjoint() {
         let mut rs = RegionSet::new();
-        rs.add(Region {
-            x: (1, 5),
-            y: (1, 5),
-            z: (1, 5),
-        });
+        rs.add(Region::new((1, 5), (1, 5), (1, 5)));
         assert_eq!(rs.total_volume(), 125);
-        rs.add(Region {
-            x: (11, 15),
-            y: (11, 15),
-            z: (11, 15),
-        });
+        rs.add(Region::new((11, 15), (11, 15), (11, 15)));
         assert_eq!(rs.total_volume(), 250);
     }
 
     #[test]
     fn test_add_contained() {
         let mut rs = RegionSet::new();
-        rs.add(Region {
-            x: (1, 5),
-            y: (1, 5),
-            z: (1, 5),
-        });
-        rs.add(Region {
-            x: (3, 3),
-            y: (3, 3),
-            z: (3, 3),
-        });
+        rs.add(Region::new((1, 5), (1, 5), (1, 5)));
+        rs.add(Region::new((3, 3), (3, 3), (3, 3)));
         assert_eq!(rs.total_volume(), 125);
     }
 
     #[test]
     fn test_add_overlap() {
         let mut rs = RegionSet::new();
-        rs.add(Region {
-            x: (1, 2),
-            y: (1, 2),
-            z: (1, 2),
-        });
-        rs.add(Region {
-            x: (2, 3),
-            y: (2, 3),
-            z: (2, 3),
-        });
+        rs.add(Region::new((1, 2), (1, 2), (1, 2)));
+        rs.add(Region::new((2, 3), (2, 3), (2, 3)));
         assert_eq!(rs.total_volume(), 15);
-        rs.add(Region {
-            x: (1, 2),
-            y: (1, 2),
-            z: (1, 10),
-        });
+        rs.add(Region::new((1, 2), (1, 2), (1, 10)));
         assert_eq!(rs.total_volume(), 46);
         //assert_eq!(rs.regions.len(), 7);
 
         rs = RegionSet::new();
-        rs.add(Region::from_str("x=1..3,y=1..3,z=1..1"));
+        rs.add(region("x=1..3,y=1..3,z=1..1"));
         assert_eq!(rs.regions.len(), 1);
         assert_eq!(rs.total_volume(), 9);
-        rs.add(Region::from_str("x=1..6,y=2..4,z=1..1"));
+        rs.add(region("x=1..6,y=2..4,z=1..1"));
         // would be nice to just need 3 here...
         //assert_eq!(rs.regions.len(), 4);
         assert_eq!(rs.total_volume(), 21);
 
         rs = RegionSet::new();
-        rs.add(Region::from_str("x=-20..26,y=-36..17,z=-47..7"));
+        rs.add(region("x=-20..26,y=-36..17,z=-47..7"));
         assert_eq!(rs.regions.len(), 1);
         assert_eq!(rs.total_volume(), 139590);
-        rs.add(Region::from_str("x=-20..33,y=-21..23,z=-26..28"));
+        rs.add(region("x=-20..33,y=-21..23,z=-26..28"));
         //assert_eq!(rs.regions.len(), 8);
         assert_eq!(rs.total_volume(), 210918);
     }
@@ -525,78 +489,38 @@ mod tests {
     #[test]
     fn test_subtract_disjoint() {
         let mut rs = RegionSet::new();
-        rs.add(Region {
-            x: (1, 5),
-            y: (1, 5),
-            z: (1, 5),
-        });
+        rs.add(Region::new((1, 5), (1, 5), (1, 5)));
         assert_eq!(rs.total_volume(), 125);
-        rs.subtract(Region {
-            x: (11, 15),
-            y: (11, 15),
-            z: (11, 15),
-        });
+        rs.subtract(Region::new((11, 15), (11, 15), (11, 15)));
         assert_eq!(rs.total_volume(), 125);
     }
 
     #[test]
     fn test_subtract_overlap() {
         let mut rs = RegionSet::new();
-        rs.add(Region {
-            x: (1, 6),
-            y: (2, 4),
-            z: (1, 1),
-        });
-        rs.subtract(Region {
-            x: (1, 3),
-            y: (1, 3),
-            z: (1, 1),
-        });
+        rs.add(Region::new((1, 6), (2, 4), (1, 1)));
+        rs.subtract(Region::new((1, 3), (1, 3), (1, 1)));
         assert_eq!(rs.total_volume(), 12);
 
         rs = RegionSet::new();
-        rs.add(Region {
-            x: (1, 3),
-            y: (1, 3),
-            z: (1, 1),
-        });
-        rs.subtract(Region {
-            x: (2, 2),
-            y: (2, 2),
-            z: (1, 1),
-        });
+        rs.add(Region::new((1, 3), (1, 3), (1, 1)));
+        rs.subtract(Region::new((2, 2), (2, 2), (1, 1)));
         assert_eq!(rs.total_volume(), 8);
     }
 
     #[test]
     fn test_split_against() {
-        let r1 = Region::from_str("x=1..2,y=1..2,z=1..2");
-        let r2 = Region::from_str("x=1..2,y=1..2,z=1..2");
-        let r3 = Region::from_str("x=2..3,y=2..3,z=2..3");
+        let r1 = region("x=1..2,y=1..2,z=1..2");
+        let r2 = region("x=1..2,y=1..2,z=1..2");
+        let r3 = region("x=2..3,y=2..3,z=2..3");
         assert_eq!(r1.split_against(r2), HashSet::from([r1]));
         assert_eq!(
             r1.split_against(r3),
             HashSet::from([
-                Region {
-                    x: (3, 3),
-                    y: (2, 3),
-                    z: (2, 3)
-                },
-                Region {
-                    x: (2, 2),
-                    y: (3, 3),
-                    z: (2, 3)
-                },
-                Region {
-                    x: (2, 2),
-                    y: (2, 2),
-                    z: (3, 3)
-                },
-                Region {
-                    x: (2, 2),
-                    y: (2, 2),
-                    z: (2, 2)
-                },
+                Region::new((3, 3), (2, 3), (2, 3)),
+                Region::new((2, 2), (3, 3), (2, 3)),
+                Region::new((2, 2), (2, 2), (3, 3)),
+                Region::new((2, 2), (2, 2), (2, 2)),
             ])
         );
     }
@@ -0,0 +1,318 @@
+// Day 23 - Amphipod
+
+use std::fmt::Write;
+
+use crate::{
+    input::{Input, InputError},
+    search,
+    solution::{Answer, Solution},
+    trace,
+};
+
+const HALL_LEN: usize = 11;
+const ROOMS: usize = 4;
+const MAX_DEPTH: usize = 4;
+// Hallway spaces just outside each room, where nobody may stop
+const DOORS: [usize; ROOMS] = [2, 4, 6, 8];
+// Energy per step for A, B, C and D
+const ENERGY: [u32; ROOMS] = [1, 10, 100, 1000];
+// The rows folded away in the diagram, which part 2 puts back in
+const FOLDED: [[u8; ROOMS]; 2] = [[4, 3, 2, 1], [4, 2, 1, 3]];
+
+/// Where every amphipod is, packed into 3 bits per space (0 for empty,
+/// 1-4 for A-D). The hallway comes first, left to right, then each room
+/// from the top down, with room for the deepest burrow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct State(u128);
+
+impl State {
+    fn get(&self, space: usize) -> u8 {
+        (self.0 >> (3 * space) & 7) as u8
+    }
+
+    fn set(&mut self, space: usize, amphipod: u8) {
+        self.0 = self.0 & !(7 << (3 * space)) | (amphipod as u128) << (3 * space);
+    }
+
+    fn moved(&self, from: usize, to: usize) -> State {
+        let mut next = *self;
+        next.set(to, self.get(from));
+        next.set(from, 0);
+        next
+    }
+}
+
+fn room_space(room: usize, slot: usize) -> usize {
+    HALL_LEN + room * MAX_DEPTH + slot
+}
+
+fn amphipod_char(amphipod: u8) -> char {
+    match amphipod {
+        0 => '.',
+        a => (b'A' + a - 1) as char,
+    }
+}
+
+/// The shape of the burrow, which is all that's fixed as the amphipods
+/// move about.
+struct Burrow {
+    depth: usize,
+}
+
+impl Burrow {
+    fn slots(&self, state: &State, room: usize) -> impl Iterator<Item = u8> + '_ {
+        let state = *state;
+        (0..self.depth).map(move |slot| state.get(room_space(room, slot)))
+    }
+
+    /// Does the room only hold (if anything) the amphipods it's for?
+    fn ready(&self, state: &State, room: usize) -> bool {
+        self.slots(state, room)
+            .all(|a| a == 0 || a as usize == room + 1)
+    }
+
+    fn is_done(&self, state: &State) -> bool {
+        (0..ROOMS).all(|room| self.slots(state, room).all(|a| a as usize == room + 1))
+    }
+
+    // Is the hallway empty between `from` and `to`, not counting `from`?
+    fn clear(&self, state: &State, from: usize, to: usize) -> bool {
+        let range = if from < to {
+            from + 1..=to
+        } else {
+            to..=from - 1
+        };
+        range.into_iter().all(|pos| state.get(pos) == 0)
+    }
+
+    fn moves(&self, state: &State) -> Vec<(State, u32)> {
+        // Going home is never a mistake, so if anyone in the hallway can,
+        // don't bother with anything else.
+        for pos in 0..HALL_LEN {
+            let amphipod = state.get(pos);
+            if amphipod == 0 {
+                continue;
+            }
+            let room = amphipod as usize - 1;
+            if !self.ready(state, room) || !self.clear(state, pos, DOORS[room]) {
+                continue;
+            }
+            let slot = self.slots(state, room).filter(|&a| a == 0).count() - 1;
+            let steps = pos.abs_diff(DOORS[room]) + slot + 1;
+            let next = state.moved(pos, room_space(room, slot));
+            return vec![(next, steps as u32 * ENERGY[room])];
+        }
+
+        // Otherwise, the top amphipod in any room which isn't ready can
+        // step out into the hallway.
+        let mut moves = vec![];
+        for (room, &door) in DOORS.iter().enumerate() {
+            if self.ready(state, room) {
+                continue;
+            }
+            let Some(slot) = self.slots(state, room).position(|a| a != 0) else {
+                continue;
+            };
+            let from = room_space(room, slot);
+            let energy = ENERGY[state.get(from) as usize - 1];
+            for pos in (0..HALL_LEN).filter(|pos| !DOORS.contains(pos)) {
+                if self.clear(state, door, pos) {
+                    let steps = slot + 1 + pos.abs_diff(door);
+                    moves.push((state.moved(from, pos), steps as u32 * energy));
+                }
+            }
+        }
+        moves
+    }
+
+    /// A lower bound on the energy still needed: everyone out of place
+    /// walking straight to the top of their room, through anyone in the way.
+    fn estimate(&self, state: &State) -> u32 {
+        let mut energy = 0;
+        for pos in 0..HALL_LEN {
+            let amphipod = state.get(pos) as usize;
+            if amphipod != 0 {
+                let steps = pos.abs_diff(DOORS[amphipod - 1]) + 1;
+                energy += steps as u32 * ENERGY[amphipod - 1];
+            }
+        }
+        for (room, &door) in DOORS.iter().enumerate() {
+            for slot in 0..self.depth {
+                let amphipod = state.get(room_space(room, slot)) as usize;
+                if amphipod == 0 {
+                    continue;
+                }
+                let steps = if amphipod != room + 1 {
+                    slot + 1 + door.abs_diff(DOORS[amphipod - 1]) + 1
+                } else if self
+                    .slots(state, room)
+                    .skip(slot)
+                    .any(|a| a as usize != room + 1)
+                {
+                    // in the right room, but has to let someone else out:
+                    // out, aside, back and in again
+                    slot + 1 + 2 + 1
+                } else {
+                    0
+                };
+                energy += steps as u32 * ENERGY[amphipod - 1];
+            }
+        }
+        energy
+    }
+
+    /// The burrow diagram for a state, as in the puzzle.
+    fn render(&self, state: &State) -> String {
+        let mut out = "#############\n#".to_string();
+        out.extend((0..HALL_LEN).map(|pos| amphipod_char(state.get(pos))));
+        out.push_str("#\n");
+        for slot in 0..self.depth {
+            out.push_str(if slot == 0 { "###" } else { "  #" });
+            for room in 0..ROOMS {
+                let _ = write!(out, "{}#", amphipod_char(state.get(room_space(room, slot))));
+            }
+            out.push_str(if slot == 0 { "##\n" } else { "\n" });
+        }
+        out.push_str("  #########");
+        out
+    }
+
+    /// Least energy needed to get everyone home.
+    fn organise(&self, start: State) -> u32 {
+        let (energy, path) = search::astar(
+            start,
+            |s| self.is_done(s),
+            |s| self.moves(s),
+            |s| self.estimate(s),
+        )
+        .expect("amphipods can always be organised");
+        trace!(1, "{} moves, using {} energy", path.len() - 1, energy);
+        for state in &path {
+            trace!(2, "\n{}", self.render(state));
+        }
+        energy
+    }
+}
+
+#[derive(Debug)]
+pub struct Amphipods {
+    // the amphipods in each row of rooms, from the top
+    rows: Vec<[u8; ROOMS]>,
+}
+
+impl Amphipods {
+    fn organise(&self, rows: &[[u8; ROOMS]]) -> u32 {
+        let mut start = State(0);
+        for (slot, row) in rows.iter().enumerate() {
+            for (room, &amphipod) in row.iter().enumerate() {
+                start.set(room_space(room, slot), amphipod);
+            }
+        }
+        Burrow { depth: rows.len() }.organise(start)
+    }
+}
+
+impl Solution for Amphipods {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let mut lines = input.lines();
+        let mut next_line = |expected: &str| {
+            lines
+                .next()
+                .ok_or_else(|| input.error(&format!("expected {}", expected)))
+        };
+
+        let wall = next_line("top wall")?;
+        if wall.trim_end() != "#############" {
+            return Err(wall.error("expected top wall"));
+        }
+        let hall = next_line("hallway")?;
+        if hall.trim_end() != "#...........#" {
+            return Err(hall.error("expected an empty hallway"));
+        }
+
+        let mut rows = vec![];
+        let mut counts = [0; ROOMS];
+        for _ in 0..2 {
+            let line = next_line("row of rooms")?;
+            let mut row = [0; ROOMS];
+            for (room, amphipod) in row.iter_mut().enumerate() {
+                let col = 3 + 2 * room;
+                let field = line.get(col..col + 1).unwrap_or("");
+                *amphipod = match field {
+                    "A" | "B" | "C" | "D" => field.as_bytes()[0] - b'A' + 1,
+                    _ => return Err(line.field_error("expected amphipod A-D", field)),
+                };
+                counts[*amphipod as usize - 1] += 1;
+            }
+            rows.push(row);
+        }
+
+        let floor = next_line("bottom wall")?;
+        if floor.trim() != "#########" {
+            return Err(floor.error("expected bottom wall"));
+        }
+        if counts.iter().any(|&n| n != rows.len()) {
+            return Err(input.error("need the same number of each kind of amphipod"));
+        }
+        Ok(Self { rows })
+    }
+
+    fn part1(&self) -> Answer {
+        self.organise(&self.rows).into()
+    }
+
+    fn part2(&self) -> Answer {
+        let rows = [self.rows[0], FOLDED[0], FOLDED[1], self.rows[1]];
+        self.organise(&rows).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
+
+    #[test]
+    fn test_example() {
+        let amphipods: Amphipods = example(23);
+        assert_eq!(amphipods.part1(), 12521.into());
+        assert_eq!(amphipods.part2(), 44169.into());
+    }
+
+    #[test]
+    fn test_moves() {
+        let burrow = Burrow { depth: 2 };
+        let mut state = State(0);
+        // neither room is ready, so the top of each can go to any of the
+        // seven hallway spaces
+        for (room, slot, amphipod) in [(0, 0, 1), (0, 1, 2), (1, 0, 2), (1, 1, 1)] {
+            state.set(room_space(room, slot), amphipod);
+        }
+        assert_eq!(burrow.moves(&state).len(), 7 + 7);
+        assert!(!burrow.is_done(&state));
+
+        let rendered = burrow.render(&state);
+        assert_eq!(
+            rendered,
+            "#############\n#...........#\n###A#B#.#.###\n  #B#A#.#.#\n  #########"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Amphipods::parse(&Input::from_text(
+            "#############\n#...........#\n###B#C#B#D###\n  #A#E#C#A#\n  #########",
+        ))
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 4, col 6: expected amphipod A-D: `E`"
+        );
+
+        let err = Amphipods::parse(&Input::from_text(
+            "#############\n#...........#\n###B#C#B#D###\n  #A#D#C#B#\n  #########",
+        ))
+        .unwrap_err();
+        assert!(err.to_string().contains("same number of each"));
+    }
+}
@@ -0,0 +1,428 @@
+// Day 24 - Arithmetic Logic Unit
+//
+// MONAD is 14 copies of one 18 instruction block, each reading a digit and
+// differing only in three numbers. Between them they treat z as a stack of
+// base 26 "digits": blocks which divide z by 1 push their input plus an
+// offset, and those dividing by 26 pop a value and, unless it matches their
+// input (plus a check value), push again. z only gets back to 0 if every
+// pop matches, which pairs up the input digits: each popping digit has to
+// be its pushing partner's digit plus a fixed difference. Picking each pair
+// as large (or small) as that allows gives the answers directly.
+
+use std::fmt;
+
+use crate::{
+    input::{Cursor, Input, InputError, ParseError},
+    solution::{Answer, Solution},
+    trace,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    W,
+    X,
+    Y,
+    Z,
+}
+
+impl Register {
+    fn name(&self) -> char {
+        match self {
+            Register::W => 'w',
+            Register::X => 'x',
+            Register::Y => 'y',
+            Register::Z => 'z',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Register(Register),
+    Number(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Inp,
+    Add,
+    Mul,
+    Div,
+    Mod,
+    Eql,
+}
+
+impl Op {
+    const ALL: [Op; 6] = [Op::Inp, Op::Add, Op::Mul, Op::Div, Op::Mod, Op::Eql];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Op::Inp => "inp",
+            Op::Add => "add",
+            Op::Mul => "mul",
+            Op::Div => "div",
+            Op::Mod => "mod",
+            Op::Eql => "eql",
+        }
+    }
+}
+
+/// One ALU instruction: `inp a`, or `op a b` for the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub op: Op,
+    pub a: Register,
+    pub b: Option<Operand>,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.op.name(), self.a.name())?;
+        match self.b {
+            Some(Operand::Register(r)) => write!(f, " {}", r.name()),
+            Some(Operand::Number(n)) => write!(f, " {}", n),
+            None => Ok(()),
+        }
+    }
+}
+
+fn read_register(c: &mut Cursor) -> Result<Register, ParseError> {
+    match c.next_char() {
+        Some('w') => Ok(Register::W),
+        Some('x') => Ok(Register::X),
+        Some('y') => Ok(Register::Y),
+        Some('z') => Ok(Register::Z),
+        _ => Err(c.error("expected register w, x, y or z")),
+    }
+}
+
+fn read_instruction(c: &mut Cursor) -> Result<Instruction, ParseError> {
+    let op = Op::ALL
+        .into_iter()
+        .find(|op| c.eat(op.name()))
+        .ok_or_else(|| c.error("unknown instruction"))?;
+    c.expect(" ")?;
+    let a = read_register(c)?;
+    let b = match op {
+        Op::Inp => None,
+        _ => {
+            c.expect(" ")?;
+            Some(match c.peek() {
+                Some('w' | 'x' | 'y' | 'z') => Operand::Register(read_register(c)?),
+                _ => Operand::Number(c.number()?),
+            })
+        }
+    };
+    Ok(Instruction { op, a, b })
+}
+
+/// Read an ALU program, one instruction per line.
+pub fn parse_program(input: &Input) -> Result<Vec<Instruction>, InputError> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.parse_with(read_instruction))
+        .collect()
+}
+
+/// Ways an ALU program can crash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AluError {
+    /// `inp` with nothing left to read
+    NoInput,
+    DivideByZero,
+    /// `mod a b` with a < 0 or b <= 0
+    BadModulo,
+    /// `add`, `mul` or `div` with a result too big for a register
+    Overflow,
+}
+
+impl fmt::Display for AluError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AluError::NoInput => write!(f, "ran out of input"),
+            AluError::DivideByZero => write!(f, "division by zero"),
+            AluError::BadModulo => write!(f, "modulo of a negative number or by zero or less"),
+            AluError::Overflow => write!(f, "arithmetic overflow"),
+        }
+    }
+}
+
+/// The ALU's registers, which programs run against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Alu {
+    registers: [i64; 4],
+}
+
+impl Alu {
+    pub fn get(&self, register: Register) -> i64 {
+        self.registers[register as usize]
+    }
+
+    /// Run `program`, reading whatever `inp` needs from `input`.
+    pub fn run(
+        &mut self,
+        program: &[Instruction],
+        input: impl IntoIterator<Item = i64>,
+    ) -> Result<(), AluError> {
+        let mut input = input.into_iter();
+        for inst in program {
+            let a = self.get(inst.a);
+            let b = match inst.b {
+                Some(Operand::Register(r)) => self.get(r),
+                Some(Operand::Number(n)) => n,
+                None => 0,
+            };
+            self.registers[inst.a as usize] = match inst.op {
+                Op::Inp => input.next().ok_or(AluError::NoInput)?,
+                Op::Add => a.checked_add(b).ok_or(AluError::Overflow)?,
+                Op::Mul => a.checked_mul(b).ok_or(AluError::Overflow)?,
+                Op::Div if b == 0 => return Err(AluError::DivideByZero),
+                // Rust's integer division also truncates towards zero
+                Op::Div => a.checked_div(b).ok_or(AluError::Overflow)?,
+                Op::Mod if a < 0 || b <= 0 => return Err(AluError::BadModulo),
+                Op::Mod => a % b,
+                Op::Eql => i64::from(a == b),
+            };
+        }
+        Ok(())
+    }
+}
+
+// Every MONAD block is this, except for the numbers on the lines below.
+const BLOCK: &str = "\
+inp w
+mul x 0
+add x z
+mod x 26
+div z 1
+add x 0
+eql x w
+eql x 0
+mul y 0
+add y 25
+mul y x
+add y 1
+mul z y
+mul y 0
+add y w
+add y 0
+mul y x
+add z y";
+const DIV_LINE: usize = 4;
+const CHECK_LINE: usize = 5;
+const OFFSET_LINE: usize = 15;
+
+/// Input digit `right` must be digit `left` plus `diff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pair {
+    left: usize,
+    right: usize,
+    diff: i64,
+}
+
+#[derive(Debug)]
+pub struct Monad {
+    program: Vec<Instruction>,
+    pairs: Vec<Pair>,
+}
+
+impl Monad {
+    // Work out the digit pairs from each block's numbers, or None if the
+    // program isn't shaped the way this expects.
+    fn analyse(program: &[Instruction]) -> Option<Vec<Pair>> {
+        let template = parse_program(&Input::from_text(BLOCK)).expect("valid block");
+        if program.len() != 14 * template.len() {
+            return None;
+        }
+
+        let mut pairs = vec![];
+        // digits pushed but not yet popped, with their offsets
+        let mut stack: Vec<(usize, i64)> = vec![];
+        for (digit, block) in program.chunks(template.len()).enumerate() {
+            let mut numbers = [0; 3];
+            for (line, (inst, expected)) in block.iter().zip(&template).enumerate() {
+                let slot = [DIV_LINE, CHECK_LINE, OFFSET_LINE]
+                    .iter()
+                    .position(|&l| l == line);
+                match (slot, inst.b) {
+                    (Some(slot), Some(Operand::Number(n)))
+                        if (inst.op, inst.a) == (expected.op, expected.a) =>
+                    {
+                        numbers[slot] = n
+                    }
+                    (None, _) if inst == expected => {}
+                    _ => return None,
+                }
+            }
+            let [div, check, offset] = numbers;
+            match div {
+                // x can never equal w here, so this always pushes
+                1 if check > 9 => stack.push((digit, offset)),
+                26 => {
+                    let (left, left_offset) = stack.pop()?;
+                    let diff = left_offset + check;
+                    // otherwise no pair of digits 1-9 is that far apart
+                    if diff.abs() > 8 {
+                        return None;
+                    }
+                    pairs.push(Pair {
+                        left,
+                        right: digit,
+                        diff,
+                    });
+                }
+                _ => return None,
+            }
+        }
+        stack.is_empty().then_some(pairs)
+    }
+
+    // The model number with each pair's left digit chosen by `pick` from
+    // those which keep both digits in 1..=9.
+    fn model_number(&self, pick: impl Fn(i64, i64) -> i64) -> Result<i64, String> {
+        let mut digits = [0; 14];
+        for pair in &self.pairs {
+            let lowest = 1.max(1 - pair.diff);
+            let highest = 9.min(9 - pair.diff);
+            digits[pair.left] = pick(lowest, highest);
+            digits[pair.right] = digits[pair.left] + pair.diff;
+            trace!(
+                1,
+                "digit {} = digit {} {:+}: {} {}",
+                pair.right + 1,
+                pair.left + 1,
+                pair.diff,
+                digits[pair.left],
+                digits[pair.right]
+            );
+        }
+        let number = digits.iter().fold(0, |n, d| n * 10 + d);
+
+        // Double check with the real thing.
+        let mut alu = Alu::default();
+        alu.run(&self.program, digits)
+            .map_err(|e| format!("MONAD crashed on {}: {}", number, e))?;
+        if alu.get(Register::Z) != 0 {
+            return Err(format!("MONAD rejected {}", number));
+        }
+        Ok(number)
+    }
+}
+
+impl Solution for Monad {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let program = parse_program(input)?;
+        let pairs = Self::analyse(&program)
+            .ok_or_else(|| input.error("program doesn't look like MONAD"))?;
+        Ok(Self { program, pairs })
+    }
+
+    fn part1(&self) -> Answer {
+        self.model_number(|_, highest| highest).into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.model_number(|lowest, _| lowest).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
+
+    fn program(text: &str) -> Vec<Instruction> {
+        parse_program(&Input::from_text(text)).unwrap()
+    }
+
+    #[test]
+    fn test_alu() {
+        // the examples from the puzzle
+        let mut alu = Alu::default();
+        alu.run(&program("inp x\nmul x -1"), [7]).unwrap();
+        assert_eq!(alu.get(Register::X), -7);
+
+        let three_times = program("inp z\ninp x\nmul z 3\neql z x");
+        let mut alu = Alu::default();
+        alu.run(&three_times, [2, 6]).unwrap();
+        assert_eq!(alu.get(Register::Z), 1);
+        alu.run(&three_times, [2, 7]).unwrap();
+        assert_eq!(alu.get(Register::Z), 0);
+
+        let bits = program(
+            "inp w\nadd z w\nmod z 2\ndiv w 2\nadd y w\nmod y 2\n\
+             div w 2\nadd x w\nmod x 2\ndiv w 2\nmod w 2",
+        );
+        let mut alu = Alu::default();
+        alu.run(&bits, [13]).unwrap();
+        let registers = [Register::W, Register::X, Register::Y, Register::Z];
+        assert_eq!(registers.map(|r| alu.get(r)), [1, 1, 0, 1]);
+    }
+
+    #[test]
+    fn test_alu_errors() {
+        let mut alu = Alu::default();
+        assert_eq!(
+            alu.run(&program("inp w\ninp x"), [1]),
+            Err(AluError::NoInput)
+        );
+        assert_eq!(
+            alu.run(&program("div w 0"), []),
+            Err(AluError::DivideByZero)
+        );
+        assert_eq!(alu.run(&program("mod w -2"), []), Err(AluError::BadModulo));
+        assert_eq!(
+            alu.run(&program("inp w\nmul w w\nmul w w"), [1 << 20]),
+            Err(AluError::Overflow)
+        );
+        assert_eq!(
+            alu.run(&program("inp w\ndiv w -1"), [i64::MIN]),
+            Err(AluError::Overflow)
+        );
+        // division truncates towards zero
+        alu.run(&program("inp w\ndiv w 2"), [-7]).unwrap();
+        assert_eq!(alu.get(Register::W), -3);
+    }
+
+    #[test]
+    fn test_parse() {
+        let inst = program("eql x -12").remove(0);
+        assert_eq!(
+            inst,
+            Instruction {
+                op: Op::Eql,
+                a: Register::X,
+                b: Some(Operand::Number(-12))
+            }
+        );
+        assert_eq!(inst.to_string(), "eql x -12");
+
+        let err = parse_program(&Input::from_text("inp w\nsub w 1")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 2, col 1: unknown instruction"
+        );
+        assert!(parse_program(&Input::from_text("add q 1")).is_err());
+    }
+
+    // There's no example MONAD in the puzzle, so this is one made up from
+    // the same block with different numbers.
+    #[test]
+    fn test_example() {
+        let monad: Monad = example(24);
+        assert_eq!(monad.part1(), 99394899891971i64.into());
+        assert_eq!(monad.part2(), 92171126131911i64.into());
+    }
+
+    #[test]
+    fn test_not_monad() {
+        let input = Input::from_text("inp w\nadd z w");
+        assert!(Monad::parse(&input).is_err());
+
+        // the example, but with one pair's digits too far apart to fit
+        let text = std::fs::read_to_string("inputs/examples/day24.txt").unwrap();
+        let text = text.replacen("add x -8", "add x -100", 1);
+        assert!(Monad::parse(&Input::from_text(&text)).is_err());
+    }
+}
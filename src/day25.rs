@@ -0,0 +1,168 @@
+// Day 25 - Sea Cucumber
+
+use crate::{
+    grid::{Grid, Pos},
+    input::{Input, InputError, Line},
+    solution::{Answer, Solution},
+    trace,
+};
+
+// Puzzle inputs settle within a few hundred steps; any floor still moving
+// after this many is taken to be going round in circles.
+const MAX_STEPS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Spot {
+    Empty,
+    East,
+    South,
+}
+
+fn read_row(line: &Line) -> Result<Vec<Spot>, InputError> {
+    line.char_indices()
+        .map(|(idx, ch)| match ch {
+            '.' => Ok(Spot::Empty),
+            '>' => Ok(Spot::East),
+            'v' => Ok(Spot::South),
+            _ => Err(line.field_error("expected `.`, `>` or `v`", &line[idx..idx + ch.len_utf8()])),
+        })
+        .collect()
+}
+
+/// The sea floor, where both herds wrap around from one edge to the other.
+#[derive(Debug, Clone)]
+pub struct SeaFloor {
+    spots: Grid<Spot>,
+}
+
+impl SeaFloor {
+    fn east_of(&self, (row, col): Pos) -> Pos {
+        (row, (col + 1) % self.spots.width())
+    }
+
+    fn south_of(&self, (row, col): Pos) -> Pos {
+        ((row + 1) % self.spots.height(), col)
+    }
+
+    // Everyone in `herd` with space ahead of them moves there, all at once.
+    // Returns how many moved.
+    fn move_herd(&mut self, herd: Spot, ahead: fn(&Self, Pos) -> Pos) -> usize {
+        let moving: Vec<(Pos, Pos)> = self
+            .spots
+            .iter()
+            .filter(|&(_, &spot)| spot == herd)
+            .map(|(pos, _)| (pos, ahead(self, pos)))
+            .filter(|&(_, to)| self.spots[to] == Spot::Empty)
+            .collect();
+        for &(from, to) in &moving {
+            self.spots[from] = Spot::Empty;
+            self.spots[to] = herd;
+        }
+        moving.len()
+    }
+
+    /// One step: the east herd moves, then the south herd. Returns how many
+    /// sea cucumbers moved.
+    pub fn step(&mut self) -> usize {
+        self.move_herd(Spot::East, Self::east_of) + self.move_herd(Spot::South, Self::south_of)
+    }
+
+    /// Step until nobody moves, returning the number of that first step
+    /// where nothing happens. Some floors never settle, with the herds
+    /// going round and round the same spots, which is an error.
+    pub fn settle(&mut self) -> Result<usize, String> {
+        let mut steps = 1;
+        while self.step() > 0 {
+            trace!(2, "After {} steps:\n{}", steps, self.render());
+            if steps == MAX_STEPS {
+                return Err(format!(
+                    "the herds never settle (still moving after {} steps)",
+                    steps
+                ));
+            }
+            steps += 1;
+        }
+        trace!(1, "Nobody moves on step {}", steps);
+        Ok(steps)
+    }
+
+    pub fn render(&self) -> String {
+        self.spots.render(|spot| match spot {
+            Spot::Empty => '.',
+            Spot::East => '>',
+            Spot::South => 'v',
+        })
+    }
+}
+
+impl Solution for SeaFloor {
+    fn parse(input: &Input) -> Result<Self, InputError> {
+        let spots = Grid::parse_rows(input.lines(), read_row)?;
+        if spots.is_empty() {
+            return Err(input.error("empty sea floor"));
+        }
+        Ok(Self { spots })
+    }
+
+    fn part1(&self) -> Answer {
+        self.clone().settle().into()
+    }
+
+    fn part2(&self) -> Answer {
+        // There's no part 2 - just the last star for finishing everything.
+        Answer::Unsolved("day 25 has no part 2".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::example;
+
+    #[test]
+    fn test_example() {
+        let floor: SeaFloor = example(25);
+        assert_eq!(floor.part1(), 58.into());
+    }
+
+    #[test]
+    fn test_never_settles() {
+        let floor = SeaFloor::parse(&Input::from_text(">..\n...")).unwrap();
+        assert_eq!(
+            floor.part1(),
+            Answer::Unsolved("the herds never settle (still moving after 10000 steps)".to_string())
+        );
+        assert_eq!(
+            floor.part2(),
+            Answer::Unsolved("day 25 has no part 2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_step() {
+        let mut floor: SeaFloor = example(25);
+        floor.step();
+        assert_eq!(
+            floor.render(),
+            "....>.>v.>\n\
+             v.v>.>v.v.\n\
+             >v>>..>v..\n\
+             >>v>v>.>.v\n\
+             .>v.v...v.\n\
+             v>>.>vvv..\n\
+             ..v...>>..\n\
+             vv...>>vv.\n\
+             >.v.v..v.v"
+        );
+
+        // the east herd moves first, blocking the south herd, and both
+        // wrap around but only into spots empty at the start of their move
+        let mut floor = SeaFloor::parse(&Input::from_text("..v\n>>.\n...")).unwrap();
+        assert_eq!(floor.step(), 1);
+        assert_eq!(floor.render(), "..v\n>.>\n...");
+        assert_eq!(floor.step(), 1);
+        assert_eq!(floor.render(), "..v\n.>>\n...");
+        assert_eq!(floor.step(), 2);
+        assert_eq!(floor.render(), "...\n>>v\n...");
+    }
+}
@@ -0,0 +1,366 @@
+// Integer geometry shared between days: points and vectors in two or three
+// dimensions, axis-aligned boxes, and the 24 rotations of a cube.
+//
+// Points are positions and vectors are offsets between them, so e.g.
+// `point - point` gives a vector and `point + vector` a point.
+
+use std::{
+    array,
+    ops::{Add, AddAssign, Index, Mul, Neg, Sub, SubAssign},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<const N: usize>(pub [i64; N]);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vector<const N: usize>(pub [i64; N]);
+
+pub type Point2 = Point<2>;
+pub type Point3 = Point<3>;
+pub type Vector2 = Vector<2>;
+pub type Vector3 = Vector<3>;
+
+fn zip_with<const N: usize>(a: [i64; N], b: [i64; N], f: impl Fn(i64, i64) -> i64) -> [i64; N] {
+    array::from_fn(|i| f(a[i], b[i]))
+}
+
+impl Point2 {
+    pub const fn new(x: i64, y: i64) -> Self {
+        Point([x, y])
+    }
+}
+
+impl Point3 {
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Point([x, y, z])
+    }
+}
+
+impl Vector2 {
+    pub const fn new(x: i64, y: i64) -> Self {
+        Vector([x, y])
+    }
+}
+
+impl Vector3 {
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Vector([x, y, z])
+    }
+}
+
+impl<const N: usize> Point<N> {
+    pub const ORIGIN: Self = Point([0; N]);
+
+    pub fn x(&self) -> i64 {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> i64 {
+        self.0[1]
+    }
+
+    pub fn z(&self) -> i64 {
+        self.0[2]
+    }
+
+    pub fn manhattan(&self, other: &Self) -> i64 {
+        (*self - *other).manhattan_len()
+    }
+}
+
+impl<const N: usize> Vector<N> {
+    pub const ZERO: Self = Vector([0; N]);
+
+    pub fn x(&self) -> i64 {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> i64 {
+        self.0[1]
+    }
+
+    pub fn z(&self) -> i64 {
+        self.0[2]
+    }
+
+    /// Sum of the absolute values of each component
+    pub fn manhattan_len(&self) -> i64 {
+        self.0.iter().map(|c| c.abs()).sum()
+    }
+
+    /// Each component reduced to -1, 0 or 1; a single step in the same
+    /// direction for horizontal, vertical and 45° diagonal vectors.
+    pub fn signum(&self) -> Self {
+        Vector(self.0.map(i64::signum))
+    }
+}
+
+impl<const N: usize> Index<usize> for Point<N> {
+    type Output = i64;
+
+    fn index(&self, axis: usize) -> &i64 {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize> Index<usize> for Vector<N> {
+    type Output = i64;
+
+    fn index(&self, axis: usize) -> &i64 {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize> Add<Vector<N>> for Point<N> {
+    type Output = Point<N>;
+
+    fn add(self, v: Vector<N>) -> Point<N> {
+        Point(zip_with(self.0, v.0, |a, b| a + b))
+    }
+}
+
+impl<const N: usize> AddAssign<Vector<N>> for Point<N> {
+    fn add_assign(&mut self, v: Vector<N>) {
+        *self = *self + v;
+    }
+}
+
+impl<const N: usize> Sub<Vector<N>> for Point<N> {
+    type Output = Point<N>;
+
+    fn sub(self, v: Vector<N>) -> Point<N> {
+        Point(zip_with(self.0, v.0, |a, b| a - b))
+    }
+}
+
+impl<const N: usize> SubAssign<Vector<N>> for Point<N> {
+    fn sub_assign(&mut self, v: Vector<N>) {
+        *self = *self - v;
+    }
+}
+
+impl<const N: usize> Sub for Point<N> {
+    type Output = Vector<N>;
+
+    fn sub(self, other: Point<N>) -> Vector<N> {
+        Vector(zip_with(self.0, other.0, |a, b| a - b))
+    }
+}
+
+impl<const N: usize> Add for Vector<N> {
+    type Output = Vector<N>;
+
+    fn add(self, other: Vector<N>) -> Vector<N> {
+        Vector(zip_with(self.0, other.0, |a, b| a + b))
+    }
+}
+
+impl<const N: usize> AddAssign for Vector<N> {
+    fn add_assign(&mut self, other: Vector<N>) {
+        *self = *self + other;
+    }
+}
+
+impl<const N: usize> Sub for Vector<N> {
+    type Output = Vector<N>;
+
+    fn sub(self, other: Vector<N>) -> Vector<N> {
+        Vector(zip_with(self.0, other.0, |a, b| a - b))
+    }
+}
+
+impl<const N: usize> SubAssign for Vector<N> {
+    fn sub_assign(&mut self, other: Vector<N>) {
+        *self = *self - other;
+    }
+}
+
+impl<const N: usize> Neg for Vector<N> {
+    type Output = Vector<N>;
+
+    fn neg(self) -> Vector<N> {
+        Vector(self.0.map(|c| -c))
+    }
+}
+
+impl<const N: usize> Mul<i64> for Vector<N> {
+    type Output = Vector<N>;
+
+    fn mul(self, k: i64) -> Vector<N> {
+        Vector(self.0.map(|c| c * k))
+    }
+}
+
+/// An axis-aligned box, including both its `min` and `max` corners, so a
+/// box with `min == max` is a single cell with volume 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bounds<const N: usize> {
+    pub min: Point<N>,
+    pub max: Point<N>,
+}
+
+pub type Rect = Bounds<2>;
+pub type Cuboid = Bounds<3>;
+
+impl<const N: usize> Bounds<N> {
+    pub fn new(min: Point<N>, max: Point<N>) -> Self {
+        Self { min, max }
+    }
+
+    /// From the (inclusive) range covered on each axis.
+    pub fn from_spans(spans: [(i64, i64); N]) -> Self {
+        Self {
+            min: Point(spans.map(|s| s.0)),
+            max: Point(spans.map(|s| s.1)),
+        }
+    }
+
+    pub fn span(&self, axis: usize) -> (i64, i64) {
+        (self.min[axis], self.max[axis])
+    }
+
+    /// A copy of this box, but covering `span` on the given axis.
+    pub fn with_span(&self, axis: usize, span: (i64, i64)) -> Self {
+        let mut result = *self;
+        result.min.0[axis] = span.0;
+        result.max.0[axis] = span.1;
+        result
+    }
+
+    pub fn contains(&self, p: &Point<N>) -> bool {
+        (0..N).all(|axis| self.min[axis] <= p[axis] && p[axis] <= self.max[axis])
+    }
+
+    /// Is `other` entirely inside this box?
+    pub fn contains_box(&self, other: &Self) -> bool {
+        self.contains(&other.min) && self.contains(&other.max)
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        (0..N).all(|axis| self.min[axis] <= other.max[axis] && other.min[axis] <= self.max[axis])
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if !self.intersects(other) {
+            return None;
+        }
+        Some(Self {
+            min: Point(zip_with(self.min.0, other.min.0, i64::max)),
+            max: Point(zip_with(self.max.0, other.max.0, i64::min)),
+        })
+    }
+
+    /// Number of integer points in the box
+    pub fn volume(&self) -> i64 {
+        (0..N)
+            .map(|axis| self.max[axis] - self.min[axis] + 1)
+            .product()
+    }
+}
+
+/// One of the 24 ways of turning a cube to face a different direction: an
+/// axis permutation plus sign changes, without any mirroring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rotation {
+    // component i of the result is signs[i] * component axes[i] of the input
+    axes: [usize; 3],
+    signs: [i64; 3],
+}
+
+impl Rotation {
+    pub const IDENTITY: Self = Rotation {
+        axes: [0, 1, 2],
+        signs: [1, 1, 1],
+    };
+
+    /// All 24 rotations, starting with the identity.
+    pub fn all() -> Vec<Rotation> {
+        // Even permutations keep handedness, odd ones swap it; the signs
+        // must make up for that so nothing ends up mirrored.
+        let permutations = [
+            ([0, 1, 2], 1),
+            ([1, 2, 0], 1),
+            ([2, 0, 1], 1),
+            ([0, 2, 1], -1),
+            ([2, 1, 0], -1),
+            ([1, 0, 2], -1),
+        ];
+        let mut result = vec![];
+        for (axes, parity) in permutations {
+            for bits in 0..8 {
+                let signs: [i64; 3] = array::from_fn(|i| if bits & (1 << i) == 0 { 1 } else { -1 });
+                if signs.iter().product::<i64>() == parity {
+                    result.push(Rotation { axes, signs });
+                }
+            }
+        }
+        result
+    }
+
+    fn apply_array(&self, c: [i64; 3]) -> [i64; 3] {
+        array::from_fn(|i| self.signs[i] * c[self.axes[i]])
+    }
+
+    /// Rotate a point about the origin.
+    pub fn apply(&self, p: Point3) -> Point3 {
+        Point(self.apply_array(p.0))
+    }
+
+    pub fn apply_vector(&self, v: Vector3) -> Vector3 {
+        Vector(self.apply_array(v.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point2::new(1, 5);
+        let b = Point2::new(4, 1);
+        assert_eq!(b - a, Vector2::new(3, -4));
+        assert_eq!(a + (b - a), b);
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(Vector2::new(3, -4).signum(), Vector2::new(1, -1));
+        assert_eq!(-Vector3::new(1, -2, 3) * 2, Vector3::new(-2, 4, -6));
+        assert_eq!(Point3::new(1, 2, 3).z(), 3);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let a = Cuboid::from_spans([(10, 12), (10, 12), (10, 12)]);
+        let b = Cuboid::from_spans([(11, 13), (11, 13), (11, 13)]);
+        let c = Cuboid::from_spans([(20, 20), (0, 0), (0, 0)]);
+        assert_eq!(a.volume(), 27);
+        assert!(a.intersects(&b) && !a.intersects(&c));
+        assert_eq!(a.intersection(&b).unwrap().volume(), 8);
+        assert_eq!(a.intersection(&c), None);
+        assert!(a.contains(&Point3::new(12, 10, 11)));
+        assert!(!a.contains(&Point3::new(13, 10, 11)));
+        assert!(a.contains_box(&a.intersection(&b).unwrap()));
+        assert!(!a.contains_box(&b));
+        assert_eq!(a.with_span(1, (0, 0)).span(1), (0, 0));
+
+        let r = Rect::new(Point2::new(20, -10), Point2::new(30, -5));
+        assert!(r.contains(&Point2::new(28, -7)));
+        assert_eq!(r.volume(), 66);
+    }
+
+    #[test]
+    fn test_rotations() {
+        let all = Rotation::all();
+        assert_eq!(all.len(), 24);
+        assert_eq!(all[0], Rotation::IDENTITY);
+
+        // Every rotation of an asymmetric point is different...
+        let p = Point3::new(1, 2, 3);
+        let rotated: HashSet<Point3> = all.iter().map(|r| r.apply(p)).collect();
+        assert_eq!(rotated.len(), 24);
+        // ...and none of them are mirror images.
+        assert!(!rotated.contains(&Point3::new(-1, 2, 3)));
+        assert!(rotated.contains(&Point3::new(2, -1, 3)));
+    }
+}
@@ -0,0 +1,275 @@
+// A rectangular grid of cells, for the puzzles given as a block of digits
+// or `#`/`.` pixels. Positions are (row, col), with (0, 0) top left.
+
+use std::ops::{Index, IndexMut};
+
+use crate::input::{InputError, Line};
+
+pub type Pos = (usize, usize);
+
+// Offsets in (row, col) order; the orthogonal ones come first in both, in
+// the order up, down, left, right.
+const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const ALL_AROUND: [(isize, isize); 8] = [
+    (-1, 0),
+    (1, 0),
+    (0, -1),
+    (0, 1),
+    (-1, -1),
+    (-1, 1),
+    (1, -1),
+    (1, 1),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    // row-major
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// A grid with each cell given by `f(pos)`.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Pos) -> T) -> Self {
+        let mut cells = Vec::with_capacity(width * height);
+        for row in 0..height {
+            for col in 0..width {
+                cells.push(f((row, col)));
+            }
+        }
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parse one row per line, with `parse_row` turning a line into cells.
+    /// Every row must be the same width.
+    pub fn parse_rows<'a>(
+        lines: impl IntoIterator<Item = Line<'a>>,
+        parse_row: impl Fn(&Line<'a>) -> Result<Vec<T>, InputError>,
+    ) -> Result<Self, InputError> {
+        let mut cells = vec![];
+        let mut width = 0;
+        let mut height = 0;
+        for line in lines {
+            let row = parse_row(&line)?;
+            if height == 0 {
+                width = row.len();
+            } else if row.len() != width {
+                return Err(line.error(&format!("expected {} columns", width)));
+            }
+            cells.extend(row);
+            height += 1;
+        }
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Number of cells
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.0 < self.height && pos.1 < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        if self.contains(pos) {
+            Some(&self.cells[pos.0 * self.width + pos.1])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self.cells[pos.0 * self.width + pos.1])
+        } else {
+            None
+        }
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// Every cell along with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn cells_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "column {} out of range", col);
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    fn offsets(
+        &self,
+        pos: Pos,
+        deltas: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = Pos> + '_ {
+        deltas.iter().filter_map(move |&(dr, dc)| {
+            let neighbour = (pos.0.checked_add_signed(dr)?, pos.1.checked_add_signed(dc)?);
+            self.contains(neighbour).then_some(neighbour)
+        })
+    }
+
+    /// Up, down, left and right of `pos`, where they're in the grid.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.offsets(pos, &ORTHOGONAL)
+    }
+
+    /// As `neighbours4()`, followed by the diagonals.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.offsets(pos, &ALL_AROUND)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// One line per row (without a trailing newline), each cell drawn as
+    /// the character given by `f`.
+    pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+        self.rows()
+            .map(|row| row.iter().map(&f).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Grid<u8> {
+    /// A grid of single digits, e.g. `2199943210`
+    pub fn digits<'a>(lines: impl IntoIterator<Item = Line<'a>>) -> Result<Self, InputError> {
+        Self::parse_rows(lines, Line::digits)
+    }
+}
+
+impl Grid<bool> {
+    /// A grid of `#` (true) and `.` (false) pixels
+    pub fn pixels<'a>(lines: impl IntoIterator<Item = Line<'a>>) -> Result<Self, InputError> {
+        Self::parse_rows(lines, Line::pixels)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        assert!(self.contains(pos), "{:?} is outside the grid", pos);
+        &self.cells[pos.0 * self.width + pos.1]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        assert!(self.contains(pos), "{:?} is outside the grid", pos);
+        &mut self.cells[pos.0 * self.width + pos.1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::Input;
+
+    fn sample() -> Grid<u8> {
+        Grid::digits(Input::from_text("123\n456\n").lines()).unwrap()
+    }
+
+    #[test]
+    fn test_digits() {
+        let grid = sample();
+        assert_eq!((grid.width(), grid.height(), grid.len()), (3, 2, 6));
+        assert_eq!(grid[(1, 0)], 4);
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![3, 6]);
+
+        let err = Grid::digits(Input::from_text("123\n45\n").lines()).unwrap_err();
+        assert_eq!(err.to_string(), "<input>: line 2: expected 3 columns: `45`");
+    }
+
+    #[test]
+    fn test_pixels() {
+        let input = Input::from_text("#.\n.#\n");
+        let grid = Grid::pixels(input.lines()).unwrap();
+        assert!(grid[(0, 0)] && !grid[(0, 1)]);
+        assert_eq!(grid.render(|&p| if p { '#' } else { '.' }), "#.\n.#");
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = sample();
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(
+            grid.neighbours4((1, 1)).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0), (1, 2)]
+        );
+        assert_eq!(grid.neighbours8((1, 1)).count(), 5);
+        assert_eq!(grid.neighbours8((0, 2)).count(), 3);
+    }
+
+    #[test]
+    fn test_map_and_iter() {
+        let grid = sample().map(|&d| d as i32 * 10);
+        assert_eq!(grid.iter().nth(4), Some(((1, 1), &50)));
+        let built = Grid::from_fn(3, 2, |(r, c)| (r * 3 + c + 1) as i32 * 10);
+        assert_eq!(grid, built);
+    }
+}
@@ -0,0 +1,643 @@
+// Puzzle input loading, shared by all the days.
+//
+// Everything here returns a Result rather than panicking; errors carry
+// where the input came from, the (1-based) line and column numbers and the
+// text which couldn't be understood.
+
+use std::{
+    error, fmt, fs,
+    io::{self, BufRead, Read},
+    ops::Deref,
+    path::Path,
+    str::FromStr,
+};
+
+#[derive(Debug)]
+pub enum ErrorKind {
+    Io(io::Error),
+    Invalid(String),
+}
+
+#[derive(Debug)]
+pub struct InputError {
+    /// Path of the input file, or `<stdin>` / `<input>`
+    pub source: String,
+    pub line: Option<usize>,
+    /// 1-based, in characters
+    pub column: Option<usize>,
+    pub text: String,
+    pub kind: ErrorKind,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.source)?;
+        if let Some(line) = self.line {
+            write!(f, ": line {}", line)?;
+            if let Some(column) = self.column {
+                write!(f, ", col {}", column)?;
+            }
+        }
+        match &self.kind {
+            ErrorKind::Io(e) => write!(f, ": {}", e),
+            ErrorKind::Invalid(msg) if self.text.is_empty() => write!(f, ": {}", msg),
+            ErrorKind::Invalid(msg) => write!(f, ": {}: `{}`", msg, self.text),
+        }
+    }
+}
+
+impl error::Error for InputError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match &self.kind {
+            ErrorKind::Io(e) => Some(e),
+            ErrorKind::Invalid(_) => None,
+        }
+    }
+}
+
+/// The full text of a puzzle input, along with where it came from.
+#[derive(Debug)]
+pub struct Input {
+    source: String,
+    text: String,
+}
+
+impl Input {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, InputError> {
+        let source = path.as_ref().display().to_string();
+        match fs::read_to_string(&path) {
+            Ok(text) => Ok(Self { source, text }),
+            Err(e) => Err(InputError {
+                source,
+                line: None,
+                column: None,
+                text: String::new(),
+                kind: ErrorKind::Io(e),
+            }),
+        }
+    }
+
+    pub fn from_stdin() -> Result<Self, InputError> {
+        let source = "<stdin>".to_string();
+        let mut text = String::new();
+        match io::stdin().read_to_string(&mut text) {
+            Ok(_) => Ok(Self { source, text }),
+            Err(e) => Err(InputError {
+                source,
+                line: None,
+                column: None,
+                text: String::new(),
+                kind: ErrorKind::Io(e),
+            }),
+        }
+    }
+
+    pub fn from_text(text: &str) -> Self {
+        Self {
+            source: "<input>".to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = Line<'_>> {
+        self.text.lines().enumerate().map(|(idx, text)| Line {
+            source: &self.source,
+            number: idx + 1,
+            text,
+        })
+    }
+
+    pub fn first_line(&self) -> Result<Line<'_>, InputError> {
+        self.lines()
+            .next()
+            .ok_or_else(|| self.error("input is empty"))
+    }
+
+    /// An error which doesn't relate to any particular line, e.g. missing
+    /// sections of the input.
+    pub fn error(&self, message: &str) -> InputError {
+        InputError {
+            source: self.source.clone(),
+            line: None,
+            column: None,
+            text: String::new(),
+            kind: ErrorKind::Invalid(message.to_string()),
+        }
+    }
+
+    /// Parse every line as a single value.
+    pub fn parse_lines<T: FromStr>(&self) -> Result<Vec<T>, InputError> {
+        self.lines().map(|l| l.parse()).collect()
+    }
+
+    /// Parse the first line as comma-separated values.
+    pub fn csv<T: FromStr>(&self) -> Result<Vec<T>, InputError> {
+        let line = self.first_line()?;
+        line.trim()
+            .split(',')
+            .map(|x| line.parse_field(x))
+            .collect()
+    }
+}
+
+/// A single line of input; derefs to `&str` so the usual string handling
+/// is available, with a few helpers to produce errors tied to the line.
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    source: &'a str,
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Deref for Line<'a> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.text
+    }
+}
+
+impl<'a> Line<'a> {
+    /// An error about the line as a whole, so without a column.
+    pub fn error(&self, message: &str) -> InputError {
+        InputError {
+            column: None,
+            ..self.field_error(message, self.text)
+        }
+    }
+
+    /// As `error()`, but pointing at just part of the line. If `field` is a
+    /// slice of the line, the error includes the column it starts at.
+    pub fn field_error(&self, message: &str, field: &str) -> InputError {
+        let start = self.text.as_ptr() as usize;
+        let offset = (field.as_ptr() as usize).wrapping_sub(start);
+        let column = if offset + field.len() <= self.text.len() {
+            Some(self.text[..offset].chars().count() + 1)
+        } else {
+            None
+        };
+        InputError {
+            source: self.source.to_string(),
+            line: Some(self.number),
+            column,
+            text: field.to_string(),
+            kind: ErrorKind::Invalid(message.to_string()),
+        }
+    }
+
+    /// Parse the line with a `Cursor` based parser, which must consume all
+    /// of it. Errors point at the column where the parser gave up.
+    pub fn parse_with<T>(
+        &self,
+        parser: impl FnOnce(&mut Cursor<'a>) -> Result<T, ParseError>,
+    ) -> Result<T, InputError> {
+        Cursor::parse_all(self.text, parser).map_err(|e| InputError {
+            source: self.source.to_string(),
+            line: Some(self.number),
+            column: Some(e.column),
+            text: String::new(),
+            kind: ErrorKind::Invalid(e.message),
+        })
+    }
+
+    /// Parse the whole line (ignoring surrounding whitespace).
+    pub fn parse<T: FromStr>(&self) -> Result<T, InputError> {
+        self.parse_field(self.text)
+    }
+
+    /// Parse part of this line, e.g. one of its comma-separated values.
+    pub fn parse_field<T: FromStr>(&self, field: &str) -> Result<T, InputError> {
+        let field = field.trim();
+        field
+            .parse()
+            .map_err(|_| self.field_error("invalid value", field))
+    }
+
+    /// Split into exactly two parts around `sep`.
+    pub fn split_pair(&self, sep: &str) -> Result<(&'a str, &'a str), InputError> {
+        self.text
+            .split_once(sep)
+            .ok_or_else(|| self.error(&format!("expected `{}`", sep)))
+    }
+
+    /// Each character of the line as a decimal digit.
+    pub fn digits(&self) -> Result<Vec<u8>, InputError> {
+        self.text
+            .char_indices()
+            .map(|(idx, ch)| match ch.to_digit(10) {
+                Some(d) => Ok(d as u8),
+                None => {
+                    Err(self.field_error("expected digit", &self.text[idx..idx + ch.len_utf8()]))
+                }
+            })
+            .collect()
+    }
+
+    /// Each character of the line as a pixel: `#` is on, `.` is off.
+    pub fn pixels(&self) -> Result<Vec<bool>, InputError> {
+        self.text
+            .char_indices()
+            .map(|(idx, ch)| match ch {
+                '#' => Ok(true),
+                '.' => Ok(false),
+                _ => {
+                    Err(self
+                        .field_error("expected `#` or `.`", &self.text[idx..idx + ch.len_utf8()]))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Lines read one at a time as they arrive, for input which may never end
+/// (e.g. a pipe from something still running) so can't be read up front.
+pub struct LineReader<R> {
+    source: String,
+    reader: R,
+    number: usize,
+    buf: Vec<u8>,
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub fn new(source: &str, reader: R) -> Self {
+        Self {
+            source: source.to_string(),
+            reader,
+            number: 0,
+            buf: vec![],
+        }
+    }
+
+    /// The next line, or None at the end of the stream. Waits for it if
+    /// it hasn't arrived yet. A line which isn't valid UTF-8 is an
+    /// `Invalid` error, and the lines after it can still be read; an `Io`
+    /// error means the stream is broken.
+    pub fn next_line(&mut self) -> Option<Result<Line<'_>, InputError>> {
+        self.buf.clear();
+        self.number += 1;
+        match self.reader.read_until(b'\n', &mut self.buf) {
+            Ok(0) => None,
+            Ok(_) => Some(match std::str::from_utf8(&self.buf) {
+                Ok(text) => Ok(Line {
+                    source: &self.source,
+                    number: self.number,
+                    text: text.trim_end_matches(['\n', '\r']),
+                }),
+                Err(_) => Err(InputError {
+                    source: self.source.clone(),
+                    line: Some(self.number),
+                    column: None,
+                    text: String::new(),
+                    kind: ErrorKind::Invalid("not valid UTF-8".to_string()),
+                }),
+            }),
+            Err(e) => Some(Err(InputError {
+                source: self.source.clone(),
+                line: Some(self.number),
+                column: None,
+                text: String::new(),
+                kind: ErrorKind::Io(e),
+            })),
+        }
+    }
+}
+
+impl LineReader<io::StdinLock<'static>> {
+    pub fn stdin() -> Self {
+        Self::new("<stdin>", io::stdin().lock())
+    }
+}
+
+impl LineReader<io::BufReader<fs::File>> {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, InputError> {
+        let source = path.as_ref().display().to_string();
+        match fs::File::open(&path) {
+            Ok(file) => Ok(Self::new(&source, io::BufReader::new(file))),
+            Err(e) => Err(InputError {
+                source,
+                line: None,
+                column: None,
+                text: String::new(),
+                kind: ErrorKind::Io(e),
+            }),
+        }
+    }
+}
+
+/// An error from parsing a single line (or any string), at the given
+/// column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based, in characters
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    /// Add what was being parsed, e.g. "expected `..`" becomes "expected
+    /// `..` in range".
+    pub fn context(mut self, what: &str) -> Self {
+        self.message = format!("{} in {}", self.message, what);
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "col {}: {}", self.column, self.message)
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// A position in a string being parsed, for formats with a bit more
+/// structure than can easily be handled with `split`. Each method either
+/// consumes what it's asked for or returns an error at the current column.
+#[derive(Debug, Clone)]
+pub struct Cursor<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self { text, pos: 0 }
+    }
+
+    /// Run `parser` over the whole of `text`, failing if anything is left.
+    pub fn parse_all<T>(
+        text: &'a str,
+        parser: impl FnOnce(&mut Cursor<'a>) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        let mut cursor = Cursor::new(text);
+        let value = parser(&mut cursor)?;
+        cursor.finish()?;
+        Ok(value)
+    }
+
+    pub fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    pub fn at_end(&self) -> bool {
+        self.pos == self.text.len()
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    pub fn next_char(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += ch.len_utf8();
+        Some(ch)
+    }
+
+    /// An error at the current position.
+    pub fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            column: self.text[..self.pos].chars().count() + 1,
+            message: message.to_string(),
+        }
+    }
+
+    /// Consume `s` if it comes next.
+    pub fn eat(&mut self, s: &str) -> bool {
+        if self.rest().starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn expect(&mut self, s: &str) -> Result<(), ParseError> {
+        if self.eat(s) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", s)))
+        }
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Everything up to the next whitespace (or the end).
+    pub fn word(&mut self) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// An integer, with optional leading `-`.
+    pub fn number<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let rest = self.rest();
+        let sign = usize::from(rest.starts_with('-'));
+        let len = sign
+            + rest[sign..]
+                .find(|ch: char| !ch.is_ascii_digit())
+                .unwrap_or(rest.len() - sign);
+        if len == sign {
+            return Err(self.error("expected number"));
+        }
+        let value = rest[..len]
+            .parse()
+            .map_err(|_| self.error("number out of range"))?;
+        self.pos += len;
+        Ok(value)
+    }
+
+    /// Fails unless everything has been consumed.
+    pub fn finish(&self) -> Result<(), ParseError> {
+        if self.at_end() {
+            Ok(())
+        } else {
+            Err(self.error(&format!("unexpected `{}`", self.rest())))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let input = Input::from_text("12\n\n34\n");
+        let lines: Vec<(usize, &str)> = input.lines().map(|l| (l.number, l.text)).collect();
+        assert_eq!(lines, vec![(1, "12"), (2, ""), (3, "34")]);
+    }
+
+    #[test]
+    fn test_line_reader() {
+        let mut reader = LineReader::new("<pipe>", "12\r\nx\n\n34".as_bytes());
+        let mut lines = vec![];
+        while let Some(line) = reader.next_line() {
+            let line = line.unwrap();
+            lines.push((line.number, line.text.to_string()));
+            if line.number == 2 {
+                assert_eq!(
+                    line.parse::<i32>().unwrap_err().to_string(),
+                    "<pipe>: line 2, col 1: invalid value: `x`"
+                );
+            }
+        }
+        let expected = [(1, "12"), (2, "x"), (3, ""), (4, "34")];
+        assert_eq!(lines, expected.map(|(n, s)| (n, s.to_string())));
+    }
+
+    #[test]
+    fn test_line_reader_utf8() {
+        let mut reader = LineReader::new("<pipe>", &b"12\n\xff\xfe\n34\n"[..]);
+        assert_eq!(reader.next_line().unwrap().unwrap().text, "12");
+        let err = reader.next_line().unwrap().unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::Invalid(_)));
+        assert_eq!(err.to_string(), "<pipe>: line 2: not valid UTF-8");
+        // carrying on past it
+        assert_eq!(reader.next_line().unwrap().unwrap().text, "34");
+        assert!(reader.next_line().is_none());
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let input = Input::from_text("199\n200\n208\n");
+        assert_eq!(input.parse_lines::<i32>().unwrap(), vec![199, 200, 208]);
+
+        let input = Input::from_text("199\n2x0\n208\n");
+        let err = input.parse_lines::<i32>().unwrap_err();
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.text, "2x0");
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 2, col 1: invalid value: `2x0`"
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let input = Input::from_text("3,4,3,1,2\n");
+        assert_eq!(input.csv::<usize>().unwrap(), vec![3, 4, 3, 1, 2]);
+
+        let err = Input::from_text("3,4,-3").csv::<usize>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 1, col 5: invalid value: `-3`"
+        );
+
+        let err = Input::from_text("").csv::<usize>().unwrap_err();
+        assert_eq!(err.to_string(), "<input>: input is empty");
+    }
+
+    #[test]
+    fn test_digits() {
+        let input = Input::from_text("2199943210\n39878a4921\n");
+        let mut lines = input.lines();
+        assert_eq!(
+            lines.next().unwrap().digits().unwrap(),
+            vec![2, 1, 9, 9, 9, 4, 3, 2, 1, 0]
+        );
+        let err = lines.next().unwrap().digits().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 2, col 6: expected digit: `a`"
+        );
+    }
+
+    #[test]
+    fn test_pixels() {
+        let input = Input::from_text("#.#\n#x\n");
+        let mut lines = input.lines();
+        assert_eq!(
+            lines.next().unwrap().pixels().unwrap(),
+            vec![true, false, true]
+        );
+        let err = lines.next().unwrap().pixels().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "<input>: line 2, col 2: expected `#` or `.`: `x`"
+        );
+    }
+
+    #[test]
+    fn test_field_column() {
+        let input = Input::from_text("on x=1..y");
+        let line = input.first_line().unwrap();
+        let err = line.field_error("bad", &line[5..]);
+        assert_eq!(err.column, Some(6));
+        assert_eq!(err.to_string(), "<input>: line 1, col 6: bad: `1..y`");
+
+        // Not part of the line, so no column
+        let err = line.field_error("bad", "elsewhere");
+        assert_eq!(err.column, None);
+        assert_eq!(err.to_string(), "<input>: line 1: bad: `elsewhere`");
+    }
+
+    #[test]
+    fn test_cursor() {
+        let mut c = Cursor::new("x=-10..12, rest");
+        assert!(c.expect("y=").is_err());
+        c.expect("x=").unwrap();
+        assert_eq!(c.number::<i32>(), Ok(-10));
+        c.expect("..").unwrap();
+        assert_eq!(c.number::<i32>(), Ok(12));
+        assert!(c.eat(","));
+        c.skip_whitespace();
+        assert_eq!(c.peek(), Some('r'));
+        assert_eq!(c.word(), "rest");
+        assert!(c.finish().is_ok());
+
+        let mut c = Cursor::new("1.5");
+        assert_eq!(c.number::<u8>(), Ok(1));
+        assert_eq!(
+            c.expect("..").unwrap_err().context("range").to_string(),
+            "col 2: expected `..` in range"
+        );
+        assert_eq!(
+            Cursor::new("300").number::<u8>().unwrap_err().message,
+            "number out of range"
+        );
+        assert_eq!(
+            Cursor::new("-").number::<i32>().unwrap_err().message,
+            "expected number"
+        );
+    }
+
+    #[test]
+    fn test_parse_with() {
+        let input = Input::from_text("1,2\n3;4\n");
+        let pair = |c: &mut Cursor| -> Result<(i32, i32), ParseError> {
+            let a = c.number()?;
+            c.expect(",")?;
+            Ok((a, c.number()?))
+        };
+        let mut lines = input.lines();
+        assert_eq!(lines.next().unwrap().parse_with(pair).unwrap(), (1, 2));
+        let err = lines.next().unwrap().parse_with(pair).unwrap_err();
+        assert_eq!(err.to_string(), "<input>: line 2, col 2: expected `,`");
+
+        let err = Input::from_text("1,2,3")
+            .first_line()
+            .unwrap()
+            .parse_with(pair)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "<input>: line 1, col 4: unexpected `,3`");
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let err = Input::from_path("inputs/no-such-day.txt").unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::Io(_)));
+        assert!(err.to_string().starts_with("inputs/no-such-day.txt: "));
+    }
+}
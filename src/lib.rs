@@ -0,0 +1,268 @@
+// Advent of Code 2021 solutions as a library: each day is a public module
+// with its solution type (implementing `solution::Solution`), and `DAYS`
+// lists them all. The `aoc2021` binary is a command line front-end over
+// this.
+
+pub mod automaton;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod geometry;
+pub mod grid;
+pub mod input;
+pub mod params;
+#[cfg(test)]
+mod regression;
+pub mod search;
+pub mod solution;
+pub mod trace;
+
+use std::path::Path;
+
+use input::{Input, InputError};
+use params::{Param, Params};
+use solution::{parse_boxed, Solution};
+
+/// A day's puzzle: its number, title, how to build its solution from the
+/// puzzle input, any parameters it can be run with, and how many parts it
+/// has (day 25 only has one).
+pub struct Day {
+    pub number: u32,
+    pub title: &'static str,
+    pub parse: fn(&Input) -> Result<Box<dyn Solution>, InputError>,
+    pub params: &'static [Param],
+    pub parts: usize,
+}
+
+impl Day {
+    pub fn input_path(&self) -> String {
+        format!("inputs/day{:02}.txt", self.number)
+    }
+
+    /// Is there a puzzle input to run this day with by default?
+    pub fn has_input(&self) -> bool {
+        Path::new(&self.input_path()).exists()
+    }
+
+    /// Read the given input file (`-` for stdin), or the day's usual one.
+    pub fn input(&self, path: Option<&str>) -> Result<Input, InputError> {
+        match path {
+            Some("-") => Input::from_stdin(),
+            Some(path) => Input::from_path(path),
+            None => Input::from_path(self.input_path()),
+        }
+    }
+
+    pub fn load(&self, path: Option<&str>) -> Result<Box<dyn Solution>, InputError> {
+        (self.parse)(&self.input(path)?)
+    }
+
+    /// This day's parameters, all at their defaults.
+    pub fn default_params(&self) -> Params {
+        Params::new(self.params)
+    }
+}
+
+pub const DAYS: &[Day] = &[
+    Day {
+        number: 1,
+        title: "Sonar Sweep",
+        parse: parse_boxed::<day01::SonarSweep>,
+        params: day01::PARAMS,
+        parts: 2,
+    },
+    Day {
+        number: 2,
+        title: "Dive!",
+        parse: parse_boxed::<day02::Dive>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 3,
+        title: "Binary Diagnostic",
+        parse: parse_boxed::<day03::Diagnostic>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 4,
+        title: "Giant Squid",
+        parse: parse_boxed::<day04::Game>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 5,
+        title: "Hydrothermal Venture",
+        parse: parse_boxed::<day05::Grid>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 6,
+        title: "Lanternfish",
+        parse: parse_boxed::<day06::LanternSim>,
+        params: day06::PARAMS,
+        parts: 2,
+    },
+    Day {
+        number: 7,
+        title: "The Treachery of Whales",
+        parse: parse_boxed::<day07::CrabSumSwarm>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 8,
+        title: "Seven Segment Search",
+        parse: parse_boxed::<day08::Notes>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 9,
+        title: "Smoke Basin",
+        parse: parse_boxed::<day09::HeightMap>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 10,
+        title: "Syntax Scoring",
+        parse: parse_boxed::<day10::NavSystem>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 11,
+        title: "Dumbo Octopus",
+        parse: parse_boxed::<day11::OctoMap>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 12,
+        title: "Passage Pathing",
+        parse: parse_boxed::<day12::CaveGraph>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 13,
+        title: "Transparent Origami",
+        parse: parse_boxed::<day13::PaperDots>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 14,
+        title: "Extended Polymerization",
+        parse: parse_boxed::<day14::Polymer>,
+        params: day14::PARAMS,
+        parts: 2,
+    },
+    Day {
+        number: 15,
+        title: "Chiton",
+        parse: parse_boxed::<day15::RiskMaze>,
+        params: day15::PARAMS,
+        parts: 2,
+    },
+    Day {
+        number: 16,
+        title: "Packet Decoder",
+        parse: parse_boxed::<day16::PacketReader>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 17,
+        title: "Trick Shot",
+        parse: parse_boxed::<day17::Probe>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 18,
+        title: "Snailfish",
+        parse: parse_boxed::<day18::Homework>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 19,
+        title: "Beacon Scanner",
+        parse: parse_boxed::<day19::ScannerMap>,
+        params: day19::PARAMS,
+        parts: 2,
+    },
+    Day {
+        number: 20,
+        title: "Trench Map",
+        parse: parse_boxed::<day20::Image>,
+        params: day20::PARAMS,
+        parts: 2,
+    },
+    Day {
+        number: 21,
+        title: "Dirac Dice",
+        parse: parse_boxed::<day21::DiracDice>,
+        params: day21::PARAMS,
+        parts: 2,
+    },
+    Day {
+        number: 22,
+        title: "Reactor Reboot",
+        parse: parse_boxed::<day22::Reactor>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 23,
+        title: "Amphipod",
+        parse: parse_boxed::<day23::Amphipods>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 24,
+        title: "Arithmetic Logic Unit",
+        parse: parse_boxed::<day24::Monad>,
+        params: &[],
+        parts: 2,
+    },
+    Day {
+        number: 25,
+        title: "Sea Cucumber",
+        parse: parse_boxed::<day25::SeaFloor>,
+        params: &[],
+        parts: 1,
+    },
+];
+
+pub fn find_day(number: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.number == number)
+}
@@ -1,6 +1,174 @@
-mod day22;
+mod bench;
+mod cli;
+mod report;
+mod sonar;
 
-fn main() {
-    day22::step1();
-    day22::step2();
+use std::{process::ExitCode, time::Instant};
+
+use aoc2021::{find_day, solution::Answer, trace, Day, DAYS};
+use cli::{Command, Format, Selection, Trace};
+use report::RunResult;
+
+fn select_days(selection: Selection) -> Result<Vec<&'static Day>, String> {
+    match selection {
+        // Not every day has its puzzle input checked in (yet).
+        Selection::All => Ok(DAYS
+            .iter()
+            .filter(|day| {
+                if !day.has_input() {
+                    eprintln!("skipping day {}: no {}", day.number, day.input_path());
+                }
+                day.has_input()
+            })
+            .collect()),
+        Selection::Day(n) => match find_day(n) {
+            Some(day) => Ok(vec![day]),
+            None => Err(format!("no solution for day {} (try `list`)", n)),
+        },
+    }
+}
+
+// Days without one of the parts skip it, unless it's the only day asked for.
+fn select_parts(days: &[&Day], part: Option<usize>) -> Result<Vec<usize>, String> {
+    match (part, days) {
+        (None, _) => Ok(vec![1, 2]),
+        (Some(p), [day]) if p > day.parts => Err(format!(
+            "no part {} - day {} has just the one",
+            p, day.number
+        )),
+        (Some(p @ (1 | 2)), _) => Ok(vec![p]),
+        (Some(p), _) => Err(format!("no part {} - each day has parts 1 and 2", p)),
+    }
+}
+
+fn run(
+    selection: Selection,
+    part: Option<usize>,
+    path: Option<&str>,
+    format: Format,
+    traces: &[Trace],
+    overrides: &[(String, i64)],
+) -> Result<(), String> {
+    let days = select_days(selection)?;
+    let parts = select_parts(&days, part)?;
+
+    // Check every override means something before running anything.
+    let mut params: Vec<_> = days.iter().map(|day| day.default_params()).collect();
+    for (name, value) in overrides {
+        let mut used = false;
+        for p in params.iter_mut().filter(|p| p.declares(name)) {
+            p.set(name, *value)?;
+            used = true;
+        }
+        if !used {
+            return Err(format!("no day being run has a parameter `{}`", name));
+        }
+    }
+
+    for t in traces {
+        match t.day {
+            Some(day) => trace::set_level(day, t.level),
+            None => days
+                .iter()
+                .for_each(|day| trace::set_level(day.number, t.level)),
+        }
+    }
+
+    // An unsolved part doesn't stop the rest, but still fails the run.
+    let mut unsolved = 0;
+    for (day, params) in days.into_iter().zip(params) {
+        let input = day.input(path).map_err(|e| e.to_string())?;
+        let start = Instant::now();
+        let mut solution = (day.parse)(&input).map_err(|e| e.to_string())?;
+        solution.configure(&params);
+        let parse = start.elapsed();
+        for &p in parts.iter().filter(|&&p| p <= day.parts) {
+            let start = Instant::now();
+            let answer = solution.solve(p);
+            let result = RunResult {
+                day: day.number,
+                part: p,
+                answer,
+                input: input.source(),
+                parse,
+                solve: start.elapsed(),
+            };
+            if let Answer::Unsolved(_) = &result.answer {
+                unsolved += 1;
+            }
+            match (format, &result.answer) {
+                (Format::Text, Answer::Unsolved(why)) => {
+                    eprintln!("error: day {} part {}: {}", day.number, p, why)
+                }
+                (Format::Text, _) => println!("{}", result.text()),
+                (Format::Json, _) => println!("{}", result.json()),
+            }
+        }
+    }
+    match unsolved {
+        0 => Ok(()),
+        1 => Err("1 part has no answer".to_string()),
+        n => Err(format!("{} parts have no answer", n)),
+    }
+}
+
+fn list() {
+    for day in DAYS {
+        let params: Vec<String> = day.params.iter().map(|p| p.to_string()).collect();
+        let parts = match day.parts {
+            1 => "part 1",
+            _ => "parts 1, 2",
+        };
+        if params.is_empty() {
+            println!("day {:2}: {} ({})", day.number, day.title, parts);
+        } else {
+            println!(
+                "day {:2}: {} ({}) [{}]",
+                day.number,
+                day.title,
+                parts,
+                params.join(", ")
+            );
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let result = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Command::Run {
+            selection,
+            part,
+            input,
+            format,
+            trace,
+            params,
+        }) => run(selection, part, input.as_deref(), format, &trace, &params),
+        Ok(Command::Bench {
+            selection,
+            part,
+            input,
+            options,
+        }) => select_days(selection).and_then(|days| {
+            let parts = select_parts(&days, part)?;
+            bench::bench(&days, &parts, input.as_deref(), &options)
+        }),
+        Ok(Command::Sonar(options)) => sonar::sonar(&options),
+        Ok(Command::List) => {
+            list();
+            Ok(())
+        }
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            Ok(())
+        }
+        Err(e) => Err(format!("{}\n\n{}", e, cli::USAGE)),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
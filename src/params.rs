@@ -0,0 +1,122 @@
+// Named numbers in a puzzle (how many steps to simulate, a threshold, ...)
+// which can be changed from the command line to explore variants. Each day
+// declares its parameters along with the defaults the puzzle asks for.
+
+use std::fmt;
+
+/// A parameter a day's solution uses, with its default value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param {
+    pub name: &'static str,
+    pub default: i64,
+    /// Smallest value that makes sense
+    pub min: i64,
+    /// Largest value that can be run (in reasonable time, without
+    /// overflowing)
+    pub max: i64,
+    pub help: &'static str,
+}
+
+impl fmt::Display for Param {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.default)
+    }
+}
+
+/// Values for a set of declared parameters: their defaults, unless set
+/// otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    declared: &'static [Param],
+    values: Vec<i64>,
+}
+
+impl Params {
+    pub fn new(declared: &'static [Param]) -> Self {
+        Self {
+            declared,
+            values: declared.iter().map(|p| p.default).collect(),
+        }
+    }
+
+    fn index(&self, name: &str) -> Option<usize> {
+        self.declared.iter().position(|p| p.name == name)
+    }
+
+    pub fn declares(&self, name: &str) -> bool {
+        self.index(name).is_some()
+    }
+
+    pub fn set(&mut self, name: &str, value: i64) -> Result<(), String> {
+        let i = self
+            .index(name)
+            .ok_or_else(|| format!("unknown parameter `{}`", name))?;
+        let Param { min, max, .. } = self.declared[i];
+        if value < min {
+            return Err(format!("{} must be at least {}", name, min));
+        }
+        if value > max {
+            return Err(format!("{} must be at most {}", name, max));
+        }
+        self.values[i] = value;
+        Ok(())
+    }
+
+    /// The value of `name`, which must be one of the declared parameters.
+    pub fn get(&self, name: &str) -> i64 {
+        match self.index(name) {
+            Some(i) => self.values[i],
+            None => panic!("parameter `{}` was never declared", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMS: &[Param] = &[
+        Param {
+            name: "steps",
+            default: 10,
+            min: 0,
+            max: 100,
+            help: "steps to run",
+        },
+        Param {
+            name: "size",
+            default: 5,
+            min: 1,
+            max: 9,
+            help: "how big",
+        },
+    ];
+
+    #[test]
+    fn test_params() {
+        let mut params = Params::new(PARAMS);
+        assert_eq!(params.get("steps"), 10);
+        assert!(params.declares("size") && !params.declares("speed"));
+        params.set("size", 7).unwrap();
+        assert_eq!((params.get("steps"), params.get("size")), (10, 7));
+        assert_eq!(
+            params.set("speed", 1),
+            Err("unknown parameter `speed`".to_string())
+        );
+        assert_eq!(
+            params.set("size", 0),
+            Err("size must be at least 1".to_string())
+        );
+        assert_eq!(
+            params.set("size", 10),
+            Err("size must be at most 9".to_string())
+        );
+        assert_eq!(PARAMS[0].to_string(), "steps=10");
+    }
+
+    #[test]
+    #[should_panic(expected = "never declared")]
+    fn test_undeclared() {
+        Params::new(PARAMS).get("speed");
+    }
+}
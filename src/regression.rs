@@ -0,0 +1,127 @@
+// Regression tests: run every day against its real input and check the
+// result matches the known-good answers recorded in inputs/answers.txt.
+
+use std::collections::HashMap;
+
+use crate::{
+    input::{Input, InputError, Line},
+    solution::Answer,
+    DAYS,
+};
+
+const ANSWERS_PATH: &str = "inputs/answers.txt";
+
+fn parse_answer(line: &Line, text: &str) -> Result<Answer, InputError> {
+    if let Some(quoted) = text.strip_prefix('"') {
+        let quoted = quoted
+            .strip_suffix('"')
+            .ok_or_else(|| line.field_error("unterminated string", text))?;
+        let mut result = String::new();
+        let mut chars = quoted.chars();
+        while let Some(ch) = chars.next() {
+            if ch != '\\' {
+                result.push(ch);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => result.push('\n'),
+                Some(esc @ ('\\' | '"')) => result.push(esc),
+                _ => return Err(line.field_error("invalid escape", text)),
+            }
+        }
+        Ok(Answer::Text(result))
+    } else {
+        Ok(Answer::Number(line.parse_field(text)?))
+    }
+}
+
+pub fn load_answers(input: &Input) -> Result<HashMap<(u32, usize), Answer>, InputError> {
+    let mut answers = HashMap::new();
+    for line in input.lines() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.splitn(3, ' ');
+        let day = line.parse_field(fields.next().unwrap_or_default())?;
+        let part = line.parse_field(fields.next().unwrap_or_default())?;
+        let answer = fields
+            .next()
+            .ok_or_else(|| line.error("expected `<day> <part> <answer>`"))?;
+        answers.insert((day, part), parse_answer(&line, answer)?);
+    }
+    Ok(answers)
+}
+
+fn check_day(number: u32) {
+    let answers = load_answers(&Input::from_path(ANSWERS_PATH).unwrap()).unwrap();
+    let day = DAYS.iter().find(|d| d.number == number).unwrap();
+    let solution = day.load(None).unwrap();
+
+    let mut checked = 0;
+    if let Some(expected) = answers.get(&(number, 1)) {
+        assert_eq!(&solution.part1(), expected, "day {} part 1", number);
+        checked += 1;
+    }
+    if let Some(expected) = answers.get(&(number, 2)) {
+        assert_eq!(&solution.part2(), expected, "day {} part 2", number);
+        checked += 1;
+    }
+    assert!(checked > 0, "no recorded answers for day {}", number);
+}
+
+macro_rules! answer_tests {
+    ($($name:ident: $day:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                check_day($day);
+            }
+        )*
+    };
+}
+
+answer_tests! {
+    test_day01: 1,
+    test_day02: 2,
+    test_day03: 3,
+    test_day04: 4,
+    test_day05: 5,
+    test_day06: 6,
+    test_day07: 7,
+    test_day08: 8,
+    test_day09: 9,
+    test_day10: 10,
+    test_day11: 11,
+    test_day12: 12,
+    test_day13: 13,
+    test_day14: 14,
+    test_day15: 15,
+    test_day16: 16,
+    test_day17: 17,
+    test_day18: 18,
+    test_day20: 20,
+    test_day21: 21,
+    test_day22: 22,
+}
+
+// Takes around two minutes even with optimisations on; run explicitly with
+// `cargo test -- --ignored` after changing day 19.
+#[test]
+#[ignore]
+fn test_day19() {
+    check_day(19);
+}
+
+#[test]
+fn test_load_answers() {
+    let input = Input::from_text("# comment\n\n1 2 345\n13 2 \"#  #\\n ## \"\n");
+    let answers = load_answers(&input).unwrap();
+    assert_eq!(answers[&(1, 2)], Answer::Number(345));
+    assert_eq!(answers[&(13, 2)], Answer::Text("#  #\n ## ".to_string()));
+
+    let err = load_answers(&Input::from_text("1 x 345")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "<input>: line 1, col 3: invalid value: `x`"
+    );
+}
@@ -0,0 +1,107 @@
+// Formatting the results of `run`, either as plain text for people or as
+// JSON (one object per line) for scripts. std only, so the JSON is written
+// by hand - it's simple enough.
+
+use std::time::Duration;
+
+use aoc2021::solution::Answer;
+
+pub struct RunResult<'a> {
+    pub day: u32,
+    pub part: usize,
+    pub answer: Answer,
+    /// Where the input came from: a path, `<stdin>` or `<input>`
+    pub input: &'a str,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+impl RunResult<'_> {
+    pub fn text(&self) -> String {
+        if self.answer.to_string().contains('\n') {
+            format!("Day {:02} part {}:\n{}", self.day, self.part, self.answer)
+        } else {
+            format!("Day {:02} part {}: {}", self.day, self.part, self.answer)
+        }
+    }
+
+    pub fn json(&self) -> String {
+        let answer = match &self.answer {
+            Answer::Number(n) => n.to_string(),
+            Answer::Text(s) => json_string(s),
+            Answer::Unsolved(why) => format!("null, \"error\": {}", json_string(why)),
+        };
+        format!(
+            "{{\"day\": {}, \"part\": {}, \"answer\": {}, \"input\": {}, \"parse_ns\": {}, \"solve_ns\": {}}}",
+            self.day,
+            self.part,
+            answer,
+            json_string(self.input),
+            self.parse.as_nanos(),
+            self.solve.as_nanos()
+        )
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut result = String::from("\"");
+    for ch in s.chars() {
+        match ch {
+            '"' => result += "\\\"",
+            '\\' => result += "\\\\",
+            '\n' => result += "\\n",
+            '\t' => result += "\\t",
+            '\r' => result += "\\r",
+            c if (c as u32) < 0x20 => result += &format!("\\u{:04x}", c as u32),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(answer: Answer) -> RunResult<'static> {
+        RunResult {
+            day: 13,
+            part: 2,
+            answer,
+            input: "inputs/day13.txt",
+            parse: Duration::from_nanos(1500),
+            solve: Duration::from_micros(20),
+        }
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("plain"), "\"plain\"");
+        assert_eq!(json_string("a\"b\\c\nd\x01"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(result(Answer::Number(842)).text(), "Day 13 part 2: 842");
+        assert_eq!(
+            result(Answer::Text("# #\n # ".to_string())).text(),
+            "Day 13 part 2:\n# #\n # "
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(
+            result(Answer::Number(842)).json(),
+            "{\"day\": 13, \"part\": 2, \"answer\": 842, \"input\": \"inputs/day13.txt\", \
+             \"parse_ns\": 1500, \"solve_ns\": 20000}"
+        );
+        assert!(result(Answer::Text("# #\n # ".to_string()))
+            .json()
+            .contains("\"answer\": \"# #\\n # \","));
+        assert!(result(Answer::Unsolved("no board wins".to_string()))
+            .json()
+            .contains("\"answer\": null, \"error\": \"no board wins\","));
+    }
+}
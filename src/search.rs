@@ -0,0 +1,360 @@
+// Graph searches over any state type: breadth- and depth-first exploration,
+// cheapest paths with Dijkstra or A*, and enumerating every path to a goal.
+// The graph is never built up front - each search is given a `neighbours`
+// function, so states can be grid positions, caves, or whole puzzle
+// configurations.
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Everything reached by a search, with the cost of getting there and the
+/// route taken.
+#[derive(Debug, Clone)]
+pub struct Search<S, C> {
+    costs: HashMap<S, C>,
+    parents: HashMap<S, S>,
+    order: Vec<S>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy> Search<S, C> {
+    fn new(start: S, zero: C) -> Self {
+        Self {
+            costs: HashMap::from([(start, zero)]),
+            parents: HashMap::new(),
+            order: vec![],
+        }
+    }
+
+    /// Cost (or number of steps) from the start to `state`, if reached.
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.costs.get(state).copied()
+    }
+
+    pub fn contains(&self, state: &S) -> bool {
+        self.costs.contains_key(state)
+    }
+
+    /// Number of states reached, including the start.
+    pub fn len(&self) -> usize {
+        self.costs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.costs.is_empty()
+    }
+
+    /// States in the order the search visited them, starting with the start.
+    pub fn order(&self) -> &[S] {
+        &self.order
+    }
+
+    /// The route from the start to `goal`, including both ends.
+    pub fn path_to(&self, goal: &S) -> Option<Vec<S>> {
+        if !self.contains(goal) {
+            return None;
+        }
+        let mut path = vec![goal.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth-first search from `start`; costs are numbers of steps, so the
+/// paths found are the shortest.
+pub fn bfs<S, N, I>(start: S, mut neighbours: N) -> Search<S, usize>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut search = Search::new(start.clone(), 0);
+    let mut queue = VecDeque::from([start]);
+    while let Some(state) = queue.pop_front() {
+        let steps = search.costs[&state] + 1;
+        for next in neighbours(&state) {
+            if !search.contains(&next) {
+                search.costs.insert(next.clone(), steps);
+                search.parents.insert(next.clone(), state.clone());
+                queue.push_back(next);
+            }
+        }
+        search.order.push(state);
+    }
+    search
+}
+
+/// Depth-first search from `start`. Costs are depths in the search tree,
+/// which aren't necessarily the shortest routes.
+pub fn dfs<S, N, I>(start: S, mut neighbours: N) -> Search<S, usize>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut search = Search::new(start.clone(), 0);
+    let mut stack = vec![(start, None)];
+    while let Some((state, parent)) = stack.pop() {
+        if let Some((parent, depth)) = parent {
+            if search.contains(&state) {
+                continue;
+            }
+            search.costs.insert(state.clone(), depth);
+            search.parents.insert(state.clone(), parent);
+        }
+        let depth = search.costs[&state] + 1;
+        let mut next: Vec<S> = neighbours(&state)
+            .into_iter()
+            .filter(|n| !search.contains(n))
+            .collect();
+        // reversed, so the first neighbour is the first one explored
+        next.reverse();
+        stack.extend(next.into_iter().map(|n| (n, Some((state.clone(), depth)))));
+        search.order.push(state);
+    }
+    search
+}
+
+// Priority queue entry, ordered so that BinaryHeap pops the lowest
+// priority first.
+struct Entry<S, C> {
+    priority: C,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> PartialEq for Entry<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<S, C: Ord> Eq for Entry<S, C> {}
+
+impl<S, C: Ord> PartialOrd for Entry<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> Ord for Entry<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+/// Dijkstra's algorithm: the cheapest cost to every state reachable from
+/// `start`, where `neighbours` gives each next state along with the
+/// (non-negative) cost of moving there.
+pub fn dijkstra<S, C, N, I>(start: S, neighbours: N) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    explore(start, |_| false, neighbours, |_| C::default())
+}
+
+/// A* search for the cheapest route from `start` to any state satisfying
+/// `is_goal`, returning its cost and path. `heuristic` must never
+/// overestimate the remaining cost; one that always returns zero makes
+/// this a Dijkstra search which stops at the goal.
+pub fn astar<S, C, G, N, I, H>(
+    start: S,
+    mut is_goal: G,
+    neighbours: N,
+    heuristic: H,
+) -> Option<(C, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    G: FnMut(&S) -> bool,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+{
+    let search = explore(start, &mut is_goal, neighbours, heuristic);
+    let goal = search.order.last().filter(|s| is_goal(s))?;
+    Some((search.costs[goal], search.path_to(goal)?))
+}
+
+// The shared part of Dijkstra and A*, stopping after visiting a goal.
+fn explore<S, C, G, N, I, H>(
+    start: S,
+    mut is_goal: G,
+    mut neighbours: N,
+    mut heuristic: H,
+) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    G: FnMut(&S) -> bool,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+{
+    let mut search = Search::new(start.clone(), C::default());
+    let mut heap = BinaryHeap::from([Entry {
+        priority: heuristic(&start),
+        cost: C::default(),
+        state: start,
+    }]);
+    while let Some(Entry { cost, state, .. }) = heap.pop() {
+        if cost > search.costs[&state] {
+            // already visited more cheaply
+            continue;
+        }
+        search.order.push(state.clone());
+        if is_goal(&state) {
+            break;
+        }
+        for (next, step) in neighbours(&state) {
+            let next_cost = cost + step;
+            if search.cost(&next).is_some_and(|c| c <= next_cost) {
+                continue;
+            }
+            search.costs.insert(next.clone(), next_cost);
+            search.parents.insert(next.clone(), state.clone());
+            heap.push(Entry {
+                priority: next_cost + heuristic(&next),
+                cost: next_cost,
+                state: next,
+            });
+        }
+    }
+    search
+}
+
+/// Walk every path from `start` which ends at a goal, calling `visit` with
+/// each one, and return how many there were. Paths stop at the first goal
+/// they reach. The states must not form a cycle, or this never finishes -
+/// put whatever is needed to prevent that (e.g. the places visited so far)
+/// into the state.
+pub fn all_paths<S, G, N, I, V>(start: S, mut is_goal: G, mut neighbours: N, mut visit: V) -> usize
+where
+    S: Clone,
+    G: FnMut(&S) -> bool,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    V: FnMut(&[S]),
+{
+    let mut count = 0;
+    let mut path = vec![];
+    // each entry is a state to try, and how long the path was when added
+    let mut stack = vec![(start, 0)];
+    while let Some((state, depth)) = stack.pop() {
+        path.truncate(depth);
+        path.push(state);
+        let state = path.last().unwrap();
+        if is_goal(state) {
+            count += 1;
+            visit(&path);
+        } else {
+            stack.extend(neighbours(state).into_iter().map(|n| (n, depth + 1)));
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 - 1 - 2 - 3
+    //     |       |
+    //     4 ----- 5      with every edge costing its higher end
+    fn edges(n: &u32) -> Vec<u32> {
+        match n {
+            0 => vec![1],
+            1 => vec![0, 2, 4],
+            2 => vec![1, 3],
+            3 => vec![2, 5],
+            4 => vec![1, 5],
+            5 => vec![3, 4],
+            _ => vec![],
+        }
+    }
+
+    fn weighted(n: &u32) -> Vec<(u32, u32)> {
+        edges(n).into_iter().map(|m| (m, m.max(*n))).collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let search = bfs(0, edges);
+        assert_eq!(search.len(), 6);
+        assert_eq!(search.order(), &[0, 1, 2, 4, 3, 5]);
+        assert_eq!(search.cost(&5), Some(3));
+        assert_eq!(search.path_to(&5), Some(vec![0, 1, 4, 5]));
+        assert_eq!(search.path_to(&9), None);
+    }
+
+    #[test]
+    fn test_dfs() {
+        let search = dfs(0, edges);
+        assert_eq!(search.order(), &[0, 1, 2, 3, 5, 4]);
+        assert_eq!(search.cost(&4), Some(5));
+        assert_eq!(search.path_to(&4), Some(vec![0, 1, 2, 3, 5, 4]));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let search = dijkstra(0, weighted);
+        assert_eq!(search.cost(&3), Some(1 + 2 + 3));
+        assert_eq!(search.cost(&5), Some(1 + 4 + 5));
+        assert_eq!(search.path_to(&5), Some(vec![0, 1, 4, 5]));
+    }
+
+    #[test]
+    fn test_astar() {
+        // on an open grid, heading for (5, 5) with Manhattan distance
+        let open = |&(r, c): &(i32, i32)| {
+            [(r - 1, c), (r + 1, c), (r, c - 1), (r, c + 1)]
+                .into_iter()
+                .filter(|&(r, c)| (0..10).contains(&r) && (0..10).contains(&c) && (r, c) != (2, 2))
+                .map(|p| (p, 1))
+        };
+        let (cost, path) = astar(
+            (0, 0),
+            |&p| p == (5, 5),
+            open,
+            |&(r, c)| (5 - r).abs() + (5 - c).abs(),
+        )
+        .unwrap();
+        assert_eq!(cost, 10);
+        assert_eq!(path.len(), 11);
+        assert_eq!(path.last(), Some(&(5, 5)));
+        assert!(!path.contains(&(2, 2)));
+
+        let unreachable = astar((0, 0), |&p| p == (20, 20), open, |_| 0);
+        assert_eq!(unreachable, None);
+    }
+
+    #[test]
+    fn test_all_paths() {
+        // paths from 0 to 5 that don't revisit anything
+        let mut found = vec![];
+        let count = all_paths(
+            vec![0],
+            |p| p.last() == Some(&5),
+            |p: &Vec<u32>| {
+                edges(p.last().unwrap())
+                    .into_iter()
+                    .filter(|n| !p.contains(n))
+                    .map(|n| [p.clone(), vec![n]].concat())
+                    .collect::<Vec<_>>()
+            },
+            |path| found.push(path.last().unwrap().clone()),
+        );
+        assert_eq!(count, 2);
+        found.sort();
+        assert_eq!(found, vec![vec![0, 1, 2, 3, 5], vec![0, 1, 4, 5]]);
+    }
+}
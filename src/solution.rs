@@ -0,0 +1,96 @@
+use std::fmt;
+
+use crate::{
+    input::{Input, InputError},
+    params::Params,
+};
+
+/// The result of solving one part of a puzzle.
+///
+/// Most puzzles have a numeric answer; a few (e.g. day 13 part 2) are
+/// read off a rendered picture, so those are returned as text. Inputs which
+/// parse fine can still turn out to have no answer, so `Unsolved` says why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+    Unsolved(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Unsolved(why) => write!(f, "no answer: {}", why),
+        }
+    }
+}
+
+// Rust: there's no blanket way to say 'any integer', so spell them out.
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Number(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(i32, i64, i128, u8, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl<T: Into<Answer>> From<Result<T, String>> for Answer {
+    fn from(result: Result<T, String>) -> Self {
+        result.map_or_else(Answer::Unsolved, Into::into)
+    }
+}
+
+/// Common interface for each day's puzzle.
+///
+/// `parse` builds the puzzle state from the full input, and each part
+/// then works from that state without changing it - anything which needs
+/// to simulate works on its own copy - so both parts can be run (and
+/// re-run) from a single parse.
+pub trait Solution {
+    fn parse(input: &Input) -> Result<Self, InputError>
+    where
+        Self: Sized;
+
+    fn part1(&self) -> Answer;
+
+    fn part2(&self) -> Answer;
+
+    /// Use these values for the day's parameters (see `Day::params`) from
+    /// now on, instead of the defaults. Days without any ignore this.
+    fn configure(&mut self, _params: &Params) {}
+
+    /// Solve part 1 or 2 by number.
+    fn solve(&self, part: usize) -> Answer {
+        match part {
+            1 => self.part1(),
+            _ => self.part2(),
+        }
+    }
+}
+
+/// Parse `input` as puzzle `S`, erasing the concrete type so that
+/// different days can live side by side in the registry.
+pub fn parse_boxed<S: Solution + 'static>(input: &Input) -> Result<Box<dyn Solution>, InputError> {
+    Ok(Box::new(S::parse(input)?))
+}
+
+/// The official example for `day`, from `inputs/examples/dayNN.txt`.
+#[cfg(test)]
+pub fn example<S: Solution>(day: u32) -> S {
+    let path = format!("inputs/examples/day{:02}.txt", day);
+    S::parse(&Input::from_path(path).unwrap()).unwrap()
+}
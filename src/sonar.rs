@@ -0,0 +1,127 @@
+// Day 1's sonar sweep on a live stream of readings, e.g. the end of a pipe
+// from a sensor, reporting the increases so far as they come in rather than
+// waiting for the end.
+
+use std::io::BufRead;
+
+use aoc2021::{
+    day01::{stream_readings, SweepStats, Window},
+    input::{ErrorKind, LineReader},
+};
+
+use crate::cli::SonarOptions;
+
+fn progress(readings: usize, windows: &[(Window, SweepStats)]) -> String {
+    let counts: Vec<String> = windows
+        .iter()
+        .map(|(window, stats)| format!("window {}: {}", window.width(), stats.increases))
+        .collect();
+    format!("{} readings, increases {}", readings, counts.join(", "))
+}
+
+// The counts so far are still reported if the stream can't be read to the
+// end, before returning the error.
+fn watch<R: BufRead>(lines: LineReader<R>, options: &SonarOptions) -> Result<(), String> {
+    let mut windows: Vec<(Window, SweepStats)> = options
+        .windows
+        .iter()
+        .map(|&width| (Window::new(width), SweepStats::default()))
+        .collect();
+    let mut readings = 0;
+    let mut skipped = 0;
+    let mut failed = None;
+
+    let stream = stream_readings(lines, |e| {
+        if let ErrorKind::Io(_) = e.kind {
+            failed = Some(e);
+        } else {
+            eprintln!("skipping {}", e);
+            skipped += 1;
+        }
+    });
+    for reading in stream {
+        readings += 1;
+        for (window, stats) in &mut windows {
+            if let Some(change) = window.push(reading) {
+                stats.add(change);
+            }
+        }
+        if readings % options.every == 0 {
+            println!("{}", progress(readings, &windows));
+        }
+    }
+
+    if readings % options.every != 0 {
+        println!("{}", progress(readings, &windows));
+    }
+    for (window, stats) in &windows {
+        let largest = match stats.largest_increase {
+            Some(n) => n.to_string(),
+            None => "-".to_string(),
+        };
+        println!(
+            "window {}: {} increases, longest run {}, largest increase {}",
+            window.width(),
+            stats.increases,
+            stats.longest_run,
+            largest
+        );
+    }
+    if skipped > 0 {
+        println!("malformed lines skipped: {}", skipped);
+    }
+    match failed {
+        Some(e) => Err(e.to_string()),
+        None => Ok(()),
+    }
+}
+
+pub fn sonar(options: &SonarOptions) -> Result<(), String> {
+    match options.input.as_str() {
+        "-" => watch(LineReader::stdin(), options),
+        path => watch(
+            LineReader::from_path(path).map_err(|e| e.to_string())?,
+            options,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, BufReader, Read};
+
+    use super::*;
+
+    // Gives its text, then fails as if the pipe broke.
+    struct Broken(&'static [u8]);
+
+    impl Read for Broken {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(io::Error::other("pipe broke"));
+            }
+            self.0.read(buf)
+        }
+    }
+
+    fn options() -> SonarOptions {
+        SonarOptions {
+            input: "-".to_string(),
+            windows: vec![1, 3],
+            every: 1,
+        }
+    }
+
+    #[test]
+    fn test_watch() {
+        let lines = LineReader::new("<input>", "199\n200\nx\n208\n".as_bytes());
+        assert_eq!(watch(lines, &options()), Ok(()));
+    }
+
+    #[test]
+    fn test_watch_read_error() {
+        let lines = LineReader::new("<input>", BufReader::new(Broken(b"199\n200\n")));
+        let err = watch(lines, &options()).unwrap_err();
+        assert!(err.contains("pipe broke"), "{}", err);
+    }
+}
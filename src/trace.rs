@@ -0,0 +1,89 @@
+// Optional step-by-step output from the days, for seeing what an algorithm
+// is up to without editing it. Each day has its own verbosity level, set
+// with `run --trace`; `trace!` lines at or below that level go to stderr.
+//
+// Level 1 is for the main results along the way, 2 for each step, and 3 for
+// the fine detail within a step. The default level of 0 means no output.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+// Per-day levels, indexed by day number (0 is unused)
+static LEVELS: [AtomicU8; 26] = [const { AtomicU8::new(0) }; 26];
+// Highest level set for any day, so most checks are a single load
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(0);
+
+/// Write a line to stderr if the calling day's trace level is at least
+/// `level`, e.g. `trace!(2, "step {}: {:?}", n, state)`.
+#[macro_export]
+macro_rules! trace {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::trace::enabled(module_path!(), $level) {
+            $crate::trace::write(module_path!(), format_args!($($arg)*));
+        }
+    };
+}
+
+pub fn set_level(day: u32, level: u8) {
+    if let Some(slot) = LEVELS.get(day as usize) {
+        slot.store(level, Ordering::Relaxed);
+        MAX_LEVEL.fetch_max(level, Ordering::Relaxed);
+    }
+}
+
+pub fn level(day: u32) -> u8 {
+    LEVELS
+        .get(day as usize)
+        .map_or(0, |slot| slot.load(Ordering::Relaxed))
+}
+
+/// The day a module belongs to, from its path, e.g. 12 for
+/// `aoc2021::day12` or `aoc2021::day12::tests`.
+pub fn day_of(module: &str) -> Option<u32> {
+    module
+        .split("::")
+        .find_map(|part| part.strip_prefix("day")?.parse().ok())
+}
+
+pub fn enabled(module: &str, level: u8) -> bool {
+    if MAX_LEVEL.load(Ordering::Relaxed) < level {
+        return false;
+    }
+    day_of(module).is_some_and(|day| self::level(day) >= level)
+}
+
+pub fn write(module: &str, args: std::fmt::Arguments) {
+    match day_of(module) {
+        Some(day) => eprintln!("day{:02}: {}", day, args),
+        None => eprintln!("{}", args),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_of() {
+        assert_eq!(day_of("aoc2021::day12"), Some(12));
+        assert_eq!(day_of("aoc2021::day05::tests"), Some(5));
+        assert_eq!(day_of("aoc2021::grid"), None);
+        assert_eq!(day_of("aoc2021::dayfoo"), None);
+    }
+
+    #[test]
+    fn test_levels() {
+        // there's no day 0, so this won't add noise to other tests
+        let module = "aoc2021::day00";
+        assert!(!enabled(module, 1));
+        set_level(0, 2);
+        assert_eq!(level(0), 2);
+        assert!(enabled(module, 1) && enabled(module, 2));
+        assert!(!enabled(module, 3));
+        assert!(!enabled("aoc2021::day24", 1));
+        set_level(0, 0);
+        assert!(!enabled(module, 1));
+        // out of range days are ignored
+        set_level(99, 1);
+        assert_eq!(level(99), 0);
+    }
+}
@@ -0,0 +1,66 @@
+// The library API, as used from outside the crate.
+
+use aoc2021::{
+    day16::BitIter,
+    day18::Sfn,
+    day19::Scanner,
+    day22::{Region, RegionSet},
+    day24::{parse_program, Alu, AluError, Register},
+    find_day,
+    geometry::{Point3, Rotation, Vector3},
+    input::Input,
+};
+
+#[test]
+fn test_solve_from_text() {
+    let day = find_day(7).unwrap();
+    let solution = (day.parse)(&Input::from_text("16,1,2,0,4,2,7,1,2,14")).unwrap();
+    assert_eq!(solution.solve(1).to_string(), "37");
+    assert_eq!(solution.solve(2).to_string(), "168");
+}
+
+#[test]
+fn test_bit_iter() {
+    let bits: Vec<i32> = BitIter::new("A5".chars().collect()).take(8).collect();
+    assert_eq!(bits, vec![1, 0, 1, 0, 0, 1, 0, 1]);
+}
+
+#[test]
+fn test_sfn() {
+    let a: Sfn = "[[[[4,3],4],4],[7,[[8,4],9]]]".parse().unwrap();
+    let sum = a.add("[1,1]".parse().unwrap()).reduce();
+    assert_eq!(sum.to_string(), "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]");
+    assert_eq!(sum.magnitude(), 1384);
+}
+
+#[test]
+fn test_scanner() {
+    let a = Scanner::new(vec![Point3::ORIGIN, Point3::new(1, 2, 3)], 0);
+    let b = a.translate(&Vector3::new(5, 0, 0));
+    assert_ne!(a, b);
+    assert_eq!(b.translate(&Vector3::new(-5, 0, 0)), a);
+    assert_eq!(a.rotate(&Rotation::IDENTITY), a);
+    assert_eq!(Point3::new(1, 2, 3).manhattan(&Point3::ORIGIN), 6);
+}
+
+#[test]
+fn test_region_set() {
+    let mut set = RegionSet::new();
+    set.add("x=10..12,y=10..12,z=10..12".parse().unwrap());
+    set.add(Region::new((11, 13), (11, 13), (11, 13)));
+    set.subtract("x=9..11,y=9..11,z=9..11".parse().unwrap());
+    assert_eq!(set.total_volume(), 38);
+}
+
+#[test]
+fn test_alu() {
+    // sum the inputs into z, then check it's even
+    let program = parse_program(&Input::from_text(
+        "inp w\nadd z w\ninp w\nadd z w\nmod z 2\neql z 0",
+    ))
+    .unwrap();
+    let mut alu = Alu::default();
+    alu.run(&program, [3, 5]).unwrap();
+    assert_eq!(alu.get(Register::Z), 1);
+    assert_eq!(Alu::default().run(&program, [3]), Err(AluError::NoInput));
+}